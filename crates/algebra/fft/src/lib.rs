@@ -1,5 +1,5 @@
 use core::fmt;
-use std::ops::{Add, Mul, MulAssign, Sub};
+use std::ops::{Add, Div, Mul, MulAssign, Neg, Sub};
 
 #[derive(Clone, Copy)]
 pub struct Complex {
@@ -11,6 +11,24 @@ impl Complex {
     pub fn new(re: f64, im: f64) -> Self {
         Self { re, im }
     }
+
+    /// Checks if both the real and imaginary parts differ from those of
+    /// `other` by at most `eps`.
+    pub fn approx_eq(&self, other: &Complex, eps: f64) -> bool {
+        (self.re - other.re).abs() <= eps && (self.im - other.im).abs() <= eps
+    }
+
+    /// Returns `1 / self`.
+    ///
+    /// The reciprocal of zero has infinite or NaN components, and values
+    /// whose squared magnitude underflows to zero behave the same way.
+    pub fn recip(&self) -> Complex {
+        let norm = self.re * self.re + self.im * self.im;
+        Complex {
+            re: self.re / norm,
+            im: -self.im / norm,
+        }
+    }
 }
 
 impl fmt::Debug for Complex {
@@ -51,6 +69,17 @@ impl Sub<Complex> for Complex {
     }
 }
 
+impl Neg for Complex {
+    type Output = Complex;
+
+    fn neg(self) -> Complex {
+        Complex {
+            re: -self.re,
+            im: -self.im,
+        }
+    }
+}
+
 impl Mul<Complex> for Complex {
     type Output = Complex;
 
@@ -62,14 +91,65 @@ impl Mul<Complex> for Complex {
     }
 }
 
+impl Div<Complex> for Complex {
+    type Output = Complex;
+
+    /// Divides by `rhs`; see [`Complex::recip`] for division by (nearly) zero.
+    fn div(self, rhs: Complex) -> Complex {
+        let norm = rhs.re * rhs.re + rhs.im * rhs.im;
+        Complex {
+            re: (self.re * rhs.re + self.im * rhs.im) / norm,
+            im: (self.im * rhs.re - self.re * rhs.im) / norm,
+        }
+    }
+}
+
 impl MulAssign<Complex> for Complex {
     fn mul_assign(&mut self, rhs: Complex) {
         *self = *self * rhs;
     }
 }
 
+/// The error returned by `try_fft` for an input whose length is not a power
+/// of two, holding that length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct NotPowerOfTwo(pub usize);
+
+/// Pads `a` with zeros up to the next power of two, so that it can be passed
+/// to `fft`.
+///
+/// # Examples
+///
+/// ```
+/// # use fft::{pad_to_pow2, Complex};
+/// let mut a = vec![Complex::new(1.0, 0.0); 5];
+/// pad_to_pow2(&mut a);
+/// assert_eq!(a.len(), 8);
+/// ```
+pub fn pad_to_pow2(a: &mut Vec<Complex>) {
+    a.resize(a.len().next_power_of_two(), Complex::new(0.0, 0.0));
+}
+
+/// Same as `fft`, but returns an error instead of computing a meaningless
+/// result if the length of `a` is not a power of two.
+pub fn try_fft(a: &mut [Complex], invert: bool) -> Result<(), NotPowerOfTwo> {
+    if !a.is_empty() && !a.len().is_power_of_two() {
+        return Err(NotPowerOfTwo(a.len()));
+    }
+    fft(a, invert);
+    Ok(())
+}
+
+/// Computes the discrete Fourier transform of `a` in place, or its inverse if
+/// `invert` is true. The length of `a` must be a power of two; see
+/// `pad_to_pow2` and `try_fft`.
 pub fn fft(a: &mut [Complex], invert: bool) {
     let n = a.len();
+    debug_assert!(
+        n == 0 || n.is_power_of_two(),
+        "length {} is not a power of two",
+        n
+    );
     let mut j = 0;
     for i in 1..n {
         let mut bit = n >> 1;
@@ -111,35 +191,505 @@ pub fn fft(a: &mut [Complex], invert: bool) {
 }
 
 pub fn multiply_polynomials(a: &[u32], b: &[u32]) -> Vec<u32> {
-    let max_len = a.len() + b.len() - 1;
-    let mut a = a
-        .iter()
-        .map(|&x| Complex::new(f64::from(x), 0.0))
-        .collect::<Vec<_>>();
-    let mut b = b
+    let mut c = Vec::new();
+    FftWorkspace::new().multiply_into(a, b, &mut c);
+    c
+}
+
+/// Multiplies two polynomials like `multiply_polynomials`, dropping the
+/// trailing zero coefficients so that the length is one more than the degree
+/// of the product. Returns `[0]` if the product is zero.
+pub fn multiply_polynomials_trimmed(a: &[u32], b: &[u32]) -> Vec<u32> {
+    let mut c = multiply_polynomials(a, b);
+    let len = c.iter().rposition(|&x| x != 0).map_or(1, |i| i + 1);
+    c.truncate(len);
+    c
+}
+
+/// Buffers reused across polynomial multiplications, so that multiplying many
+/// polynomials doesn't allocate on every call.
+#[derive(Default)]
+pub struct FftWorkspace {
+    a: Vec<Complex>,
+    b: Vec<Complex>,
+}
+
+impl FftWorkspace {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Multiplies two polynomials like `multiply_polynomials`, writing the
+    /// coefficients to `out`. Allocates only when the buffers of `self` or
+    /// `out` are too small for the product.
+    pub fn multiply_into(&mut self, a: &[u32], b: &[u32], out: &mut Vec<u32>) {
+        let max_len = a.len() + b.len() - 1;
+        let n = max_len.next_power_of_two();
+        load(&mut self.a, a.iter().copied(), n);
+        load(&mut self.b, b.iter().copied(), n);
+
+        fft(&mut self.a, false);
+        fft(&mut self.b, false);
+        self.a
+            .iter_mut()
+            .zip(self.b.iter())
+            .for_each(|(x, &y)| *x *= y);
+        fft(&mut self.a, true);
+
+        out.clear();
+        out.extend(self.a[..max_len].iter().map(|x| (x.re + 0.5) as u32));
+    }
+}
+
+/// Fills `buf` with the coefficients of `poly` padded with zeros to length `n`.
+fn load(buf: &mut Vec<Complex>, poly: impl IntoIterator<Item = u32>, n: usize) {
+    buf.clear();
+    buf.extend(poly.into_iter().map(|x| Complex::new(f64::from(x), 0.0)));
+    buf.resize(n, Complex::new(0.0, 0.0));
+}
+
+/// Multiplies two non-negative integers given as little-endian digits in the
+/// given base and returns the little-endian digits of the product without
+/// leading zeros (`[0]` if the product is zero).
+///
+/// Each digit is split into two halves below about `sqrt(base)` before the
+/// convolution, so its coefficients are bounded by roughly
+/// `2 * base * min(a.len(), b.len())`. The result is exact as long as this
+/// stays below about 2^50, e.g., for up to about 5 * 10^5 digits in base 10^9.
+///
+/// # Examples
+///
+/// ```
+/// # use fft::multiply_base;
+/// // 123456789 * 987654321 = 121932631112635269
+/// let a = vec![456789, 123];
+/// let b = vec![654321, 987];
+/// assert_eq!(multiply_base(&a, &b, 1_000_000), vec![635269, 631112, 121932]);
+/// ```
+pub fn multiply_base(a: &[u32], b: &[u32], base: u32) -> Vec<u32> {
+    assert!(base >= 2, "base must be at least 2");
+    let half = (33 - (base - 1).leading_zeros()) / 2;
+    let mask = (1 << half) - 1;
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+
+    let [a_lo, a_hi, b_lo, b_hi] = [(a, 0), (a, half), (b, 0), (b, half)].map(|(x, shift)| {
+        let mut buf = Vec::with_capacity(n);
+        load(&mut buf, x.iter().map(|&d| (d >> shift) & mask), n);
+        fft(&mut buf, false);
+        buf
+    });
+    let mut lo = Vec::with_capacity(n);
+    let mut mid = Vec::with_capacity(n);
+    let mut hi = Vec::with_capacity(n);
+    for (((&al, &ah), &bl), &bh) in a_lo.iter().zip(&a_hi).zip(&b_lo).zip(&b_hi) {
+        lo.push(al * bl);
+        mid.push(al * bh + ah * bl);
+        hi.push(ah * bh);
+    }
+    fft(&mut lo, true);
+    fft(&mut mid, true);
+    fft(&mut hi, true);
+
+    let round = |x: &Complex| (x.re + 0.5) as u128;
+    let base = u128::from(base);
+    let mut digits = Vec::with_capacity(len + 1);
+    let mut carry = 0;
+    for ((lo, mid), hi) in lo.iter().zip(&mid).zip(&hi).take(len) {
+        carry += round(lo) + (round(mid) << half) + (round(hi) << (2 * half));
+        digits.push((carry % base) as u32);
+        carry /= base;
+    }
+    while carry > 0 {
+        digits.push((carry % base) as u32);
+        carry /= base;
+    }
+    while digits.len() > 1 && digits.last() == Some(&0) {
+        digits.pop();
+    }
+    digits
+}
+
+/// Counts the subsets of `weights` summing to each value from 0 to `target`,
+/// modulo 2^64, by multiplying out the generating function
+/// `(1 + x^w1)(1 + x^w2)...` with FFT. Subsets are told apart by position, so
+/// equal weights make different subsets.
+///
+/// The sorted weights are split into halves recursively, and each group is
+/// multiplied out either with the O(K D) DP, adding its `K` weights one by one
+/// up to the degree `D` reached so far, or by multiplying the products of its
+/// halves, whichever is estimated to take fewer steps. Every product is
+/// truncated to degree `target`, so this runs in
+/// O(min(N T, S log T log N)) for `T = target` and the sum `S` of the
+/// weights, and holds O(T log N) coefficients at a time. Each coefficient is
+/// split into 32-bit halves that are convolved exactly with
+/// `convolution_arbitrary_mod`'s three-prime NTT, so there is no rounding
+/// error, and `target` can be up to `2^22 - 1`.
+///
+/// # Panics
+///
+/// Panics if `target` is `2^22` or more.
+///
+/// # Examples
+///
+/// ```
+/// # use fft::subset_sum_counts;
+/// // {}, {1}, {2}, {2'}, {1, 2}, {1, 2'}, {2, 2'}, {1, 2, 2'}
+/// assert_eq!(subset_sum_counts(&[1, 2, 2], 5), vec![1, 1, 2, 2, 1, 1]);
+/// ```
+pub fn subset_sum_counts(weights: &[u32], target: u32) -> Vec<u64> {
+    assert!(target < 1 << 22, "target must be less than 2^22");
+    let len = target as usize + 1;
+    let mut weights = weights
         .iter()
-        .map(|&x| Complex::new(f64::from(x), 0.0))
+        .copied()
+        .filter(|&w| w <= target)
         .collect::<Vec<_>>();
+    weights.sort_unstable();
+    let mut counts = subset_sum_product(&weights, len, MULTIPLY_STEPS);
+    counts.resize(len, 0);
+    counts
+}
 
-    let n = max_len.next_power_of_two();
-    a.resize(n, Complex::new(0.0, 0.0));
-    b.resize(n, Complex::new(0.0, 0.0));
+/// Multiplying two polynomials of degree `D` with `multiply_wrapping` takes
+/// about this many times `D log2(D)` steps of the DP, as it runs three exact
+/// convolutions, each through three primes with 64-bit remainders.
+const MULTIPLY_STEPS: usize = 1024;
 
-    fft(&mut a, false);
-    fft(&mut b, false);
-    a.iter_mut().zip(b.iter()).for_each(|(x, &y)| *x *= y);
-    fft(&mut a, true);
+/// Multiplies out `(1 + x^w)` over `weights`, keeping at most the first `len`
+/// coefficients, either with the DP or by multiplying the products of the two
+/// halves of `weights`, whichever `subset_sum_steps` estimates to be faster
+/// with `multiply_steps` in place of `MULTIPLY_STEPS`. Only the product of
+/// one half is kept at each level of the recursion.
+fn subset_sum_product(weights: &[u32], len: usize, multiply_steps: usize) -> Vec<u64> {
+    let (degree, dp_steps) = subset_sum_dp_steps(weights, len);
+    if dp_steps <= subset_sum_steps(weights, len, multiply_steps) {
+        let mut counts = vec![0u64; degree + 1];
+        counts[0] = 1;
+        let mut reached = 0;
+        for &w in weights {
+            let w = w as usize;
+            reached = (reached + w).min(degree);
+            let counts = &mut counts[..=reached];
+            for s in (w..counts.len()).rev() {
+                counts[s] = counts[s].wrapping_add(counts[s - w]);
+            }
+        }
+        return counts;
+    }
+    let (left, right) = weights.split_at(weights.len() / 2);
+    let left = subset_sum_product(left, len, multiply_steps);
+    multiply_wrapping(&left, &subset_sum_product(right, len, multiply_steps), len)
+}
 
-    let mut c = vec![0; n];
-    for i in 0..n {
-        c[i] = (a[i].re + 0.5) as u32;
+/// Estimates the steps `subset_sum_product` takes, in steps of the DP. This
+/// takes O(K log K) for `K` weights, or O(N log^2 N) over the recursion.
+fn subset_sum_steps(weights: &[u32], len: usize, multiply_steps: usize) -> usize {
+    let (degree, dp_steps) = subset_sum_dp_steps(weights, len);
+    if weights.len() <= 1 {
+        return dp_steps;
+    }
+    let (left, right) = weights.split_at(weights.len() / 2);
+    let split_steps = subset_sum_steps(left, len, multiply_steps)
+        + subset_sum_steps(right, len, multiply_steps)
+        + multiply_steps * multiply_size(degree);
+    dp_steps.min(split_steps)
+}
+
+/// Returns the degree of the product over `weights` truncated to `len`
+/// coefficients, and the steps of the DP computing it, where each weight `w`
+/// takes a step for each degree from `w` to the degree reached after adding
+/// it.
+fn subset_sum_dp_steps(weights: &[u32], len: usize) -> (usize, usize) {
+    weights.iter().fold((0, 0), |(degree, steps), &w| {
+        let degree = (degree + w as usize).min(len - 1);
+        (degree, steps + degree + 1 - w as usize)
+    })
+}
+
+/// Returns `D log2(D)` for the degree `D` of a product, at least 1.
+fn multiply_size(degree: usize) -> usize {
+    degree.max(1) * (usize::BITS - degree.leading_zeros()).max(1) as usize
+}
+
+/// Multiplies two polynomials with coefficients modulo 2^64, keeping at most
+/// the first `len` coefficients of the product.
+fn multiply_wrapping(a: &[u64], b: &[u64], len: usize) -> Vec<u64> {
+    let product_len = (a.len() + b.len() - 1).min(len);
+    if a.len().min(b.len()) <= 32 {
+        let mut c = vec![0u64; product_len];
+        for (i, &x) in a.iter().enumerate().take(product_len) {
+            for (j, &y) in b.iter().enumerate().take(product_len - i) {
+                c[i + j] = c[i + j].wrapping_add(x.wrapping_mul(y));
+            }
+        }
+        return c;
+    }
+
+    let (a, b) = (&a[..a.len().min(len)], &b[..b.len().min(len)]);
+    let halves = |x: &[u64]| {
+        let lo = x.iter().map(|&c| c as u32).collect::<Vec<_>>();
+        let hi = x.iter().map(|&c| (c >> 32) as u32).collect::<Vec<_>>();
+        (lo, hi)
+    };
+    let ((a_lo, a_hi), (b_lo, b_hi)) = (halves(a), halves(b));
+    // The product of the high halves is shifted by 64 bits and vanishes
+    let lo = convolution_exact(&a_lo, &b_lo);
+    let mid1 = convolution_exact(&a_lo, &b_hi);
+    let mid2 = convolution_exact(&a_hi, &b_lo);
+    (0..product_len)
+        .map(|i| (lo[i] as u64).wrapping_add(((mid1[i] + mid2[i]) as u64) << 32))
+        .collect()
+}
+
+/// NTT-friendly primes `p` with a primitive root `g`, such that `p - 1` is
+/// divisible by 2^23 or more. Their product is about 7.9 * 10^25.
+const NTT_PRIMES: [(u64, u64); 3] = [(998244353, 3), (167772161, 3), (469762049, 3)];
+
+/// The largest transform length supported by all of `NTT_PRIMES`.
+const MAX_NTT_LEN: usize = 1 << 23;
+
+/// Convolves `a` and `b` modulo an arbitrary `modulus` by convolving them
+/// modulo each of three NTT-friendly primes and recovering each exact
+/// coefficient with the Chinese remainder theorem.
+///
+/// The product may have at most 2^23 coefficients, the largest power of two
+/// dividing `998244353 - 1`. The shorter input then has at most 2^22
+/// coefficients, so the exact coefficients stay below
+/// `2^22 * (2^32 - 1)^2 < 2^86`, which is less than the product of the primes,
+/// and the result is exact for every allowed `modulus`.
+///
+/// # Panics
+///
+/// Panics if `modulus` is 0 or greater than 2^32, or if the product has more
+/// than 2^23 coefficients, i.e., `a.len() + b.len() - 1 > 2^23`.
+///
+/// # Examples
+///
+/// ```
+/// # use fft::convolution_arbitrary_mod;
+/// let m = 1_000_000_007;
+/// let a = [1, m as u32 - 1];
+/// let b = [2, 3];
+/// // (1 - x)(2 + 3x) = 2 + x - 3x^2
+/// assert_eq!(convolution_arbitrary_mod(&a, &b, m), vec![2, 1, m as u32 - 3]);
+/// ```
+pub fn convolution_arbitrary_mod(a: &[u32], b: &[u32], modulus: u64) -> Vec<u32> {
+    assert!(
+        (1..=1 << 32).contains(&modulus),
+        "modulus must be in [1, 2^32]"
+    );
+    let reduce = |x: &[u32]| {
+        x.iter()
+            .map(|&c| (u64::from(c) % modulus) as u32)
+            .collect::<Vec<_>>()
+    };
+    convolution_exact(&reduce(a), &reduce(b))
+        .into_iter()
+        .map(|x| (x % u128::from(modulus)) as u32)
+        .collect()
+}
+
+/// Computes the exact convolution of `a` and `b` with three NTTs and the
+/// Chinese remainder theorem. See `convolution_arbitrary_mod` for the limits.
+fn convolution_exact(a: &[u32], b: &[u32]) -> Vec<u128> {
+    if a.is_empty() || b.is_empty() {
+        return Vec::new();
+    }
+    let len = a.len() + b.len() - 1;
+    let n = len.next_power_of_two();
+    assert!(
+        n <= MAX_NTT_LEN,
+        "the product has {} coefficients, more than 2^23",
+        len
+    );
+    let residues = NTT_PRIMES.map(|(prime, root)| {
+        let load = |x: &[u32]| {
+            let mut buf = x.iter().map(|&c| u64::from(c) % prime).collect::<Vec<_>>();
+            buf.resize(n, 0);
+            ntt(&mut buf, prime, root, false);
+            buf
+        };
+        let mut c = load(a);
+        c.iter_mut()
+            .zip(load(b))
+            .for_each(|(x, y)| *x = *x * y % prime);
+        ntt(&mut c, prime, root, true);
+        c
+    });
+
+    // Garner's algorithm: x = r0 + p0 * (x1 + p1 * x2) with each xi below pi
+    let [(p0, _), (p1, _), (p2, _)] = NTT_PRIMES;
+    let p0_inv = mod_pow(p0 % p1, p1 - 2, p1);
+    let p01_inv = mod_pow(p0 * p1 % p2, p2 - 2, p2);
+    (0..len)
+        .map(|i| {
+            let [r0, r1, r2] = residues.each_ref().map(|r| r[i]);
+            let x1 = (r1 + p1 - r0 % p1) % p1 * p0_inv % p1;
+            let x2 = (r2 + 2 * p2 - r0 % p2 - p0 % p2 * x1 % p2) % p2 * p01_inv % p2;
+            u128::from(r0) + u128::from(p0) * (u128::from(x1) + u128::from(p1) * u128::from(x2))
+        })
+        .collect()
+}
+
+/// Computes the number-theoretic transform of `a` modulo `prime` in place, or
+/// its inverse if `invert` is true, like `fft` with `root` as the primitive
+/// root. The length of `a` must be a power of two dividing `prime - 1`.
+fn ntt(a: &mut [u64], prime: u64, root: u64, invert: bool) {
+    let n = a.len();
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j ^= bit;
+        if i < j {
+            a.swap(i, j);
+        }
+    }
+
+    let mut len = 2;
+    while len <= n {
+        let mut wlen = mod_pow(root, (prime - 1) / len as u64, prime);
+        if invert {
+            wlen = mod_pow(wlen, prime - 2, prime);
+        }
+        for i in (0..n).step_by(len) {
+            let mut w = 1;
+            for j in 0..len / 2 {
+                let u = a[i + j];
+                let v = a[i + j + len / 2] * w % prime;
+                a[i + j] = (u + v) % prime;
+                a[i + j + len / 2] = (u + prime - v) % prime;
+                w = w * wlen % prime;
+            }
+        }
+        len <<= 1;
+    }
+
+    if invert {
+        let n_inv = mod_pow(n as u64 % prime, prime - 2, prime);
+        for x in a.iter_mut() {
+            *x = *x * n_inv % prime;
+        }
     }
-    c.truncate(max_len);
-    c
+}
+
+fn mod_pow(mut base: u64, mut exp: u64, modulus: u64) -> u64 {
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % modulus;
+        }
+        base = base * base % modulus;
+        exp >>= 1;
+    }
+    result
 }
 
 #[cfg(test)]
 mod tests {
+    #[test]
+    fn complex_neg_and_approx_eq() {
+        use super::Complex;
+
+        let a = Complex::new(1.5, -2.0);
+        let b = -a;
+        assert_eq!((b.re, b.im), (-1.5, 2.0));
+        assert!((a + b).approx_eq(&Complex::new(0.0, 0.0), 0.0));
+
+        let c = Complex::new(1.0, 1.0);
+        assert!(c.approx_eq(&Complex::new(1.25, 0.75), 0.25));
+        assert!(!c.approx_eq(&Complex::new(1.25, 0.75), 0.24));
+        assert!(!c.approx_eq(&Complex::new(1.0, 1.5), 0.25));
+        assert!(!c.approx_eq(&Complex::new(f64::NAN, 1.0), 1.0));
+    }
+
+    #[test]
+    fn convolution_arbitrary_mod() {
+        let mut state = 88172645463325252u64;
+        let mut next = |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+        for modulus in [1_000_000_007, 998244353, 1 << 32, 2, 1] {
+            for (n, m) in [(1, 1), (3, 5), (40, 70), (300, 200)] {
+                // Coefficients near the modulus make the exact products far
+                // larger than any single prime
+                let mut coefficients = |len: usize| {
+                    (0..len)
+                        .map(|_| (modulus - 1 - next(5).min(modulus - 1)) as u32)
+                        .collect::<Vec<_>>()
+                };
+                let (a, b) = (coefficients(n), coefficients(m));
+                let mut expected = vec![0u64; n + m - 1];
+                for (i, &x) in a.iter().enumerate() {
+                    for (j, &y) in b.iter().enumerate() {
+                        expected[i + j] =
+                            (expected[i + j] + u64::from(x) * u64::from(y) % modulus) % modulus;
+                    }
+                }
+                let expected = expected.into_iter().map(|x| x as u32).collect::<Vec<_>>();
+                assert_eq!(super::convolution_arbitrary_mod(&a, &b, modulus), expected);
+            }
+        }
+
+        // Inputs at or above the modulus are reduced first
+        assert_eq!(
+            super::convolution_arbitrary_mod(&[10, 3], &[7], 5),
+            vec![0, 1]
+        );
+        assert!(super::convolution_arbitrary_mod(&[], &[1], 7).is_empty());
+    }
+
+    #[test]
+    fn try_fft() {
+        use super::{pad_to_pow2, Complex, NotPowerOfTwo};
+
+        let mut a = (0..6)
+            .map(|i| Complex::new(i as f64, 0.0))
+            .collect::<Vec<_>>();
+        assert_eq!(super::try_fft(&mut a, false), Err(NotPowerOfTwo(6)));
+
+        pad_to_pow2(&mut a);
+        assert_eq!(a.len(), 8);
+        assert_eq!(super::try_fft(&mut a, false), Ok(()));
+        assert_eq!(super::try_fft(&mut a, true), Ok(()));
+        for (i, c) in a.iter().enumerate() {
+            let expected = if i < 6 { i as f64 } else { 0.0 };
+            assert!(c.approx_eq(&Complex::new(expected, 0.0), 1e-9));
+        }
+        assert_eq!(super::try_fft(&mut [], false), Ok(()));
+    }
+
+    #[test]
+    fn complex_div() {
+        use super::Complex;
+
+        let values = [
+            Complex::new(1.0, 0.0),
+            Complex::new(0.0, 1.0),
+            Complex::new(3.0, -4.0),
+            Complex::new(-2.5, 0.125),
+            Complex::new(1e-3, 1e3),
+        ];
+        for &a in &values {
+            for &b in &values {
+                let c = a / b * b;
+                assert!((c.re - a.re).abs() < 1e-9 && (c.im - a.im).abs() < 1e-9);
+            }
+            let c = a.recip() * a;
+            assert!((c.re - 1.0).abs() < 1e-9 && c.im.abs() < 1e-9);
+        }
+
+        let c = Complex::new(1.0, 1.0) / Complex::new(0.0, 0.0);
+        assert!(!c.re.is_finite() && !c.im.is_finite());
+    }
+
     #[test]
     fn multiply_polynomials() {
         use super::multiply_polynomials;
@@ -159,4 +709,202 @@ mod tests {
         let c = multiply_polynomials(&a, &b);
         assert_eq!(c, vec![3, 4]);
     }
+
+    #[test]
+    fn fft_workspace() {
+        use super::{multiply_polynomials, FftWorkspace};
+
+        let mut workspace = FftWorkspace::new();
+        let mut c = Vec::new();
+        workspace.multiply_into(&[1, 2, 3, 4, 5], &[6, 7, 8, 9], &mut c);
+        assert_eq!(c, multiply_polynomials(&[1, 2, 3, 4, 5], &[6, 7, 8, 9]));
+
+        let buffers = (workspace.a.as_ptr(), workspace.b.as_ptr(), c.as_ptr());
+        for (a, b) in [
+            (vec![1, 2, 3], vec![4, 5, 6]),
+            (vec![7], vec![8, 9]),
+            (vec![3, 1, 4, 1], vec![5, 9, 2, 6, 5]),
+        ] {
+            workspace.multiply_into(&a, &b, &mut c);
+            assert_eq!(c, multiply_polynomials(&a, &b));
+            assert_eq!(
+                (workspace.a.as_ptr(), workspace.b.as_ptr(), c.as_ptr()),
+                buffers
+            );
+        }
+    }
+
+    #[test]
+    fn multiply_polynomials_trimmed() {
+        use super::multiply_polynomials_trimmed;
+
+        let a = vec![1, 2, 0, 0];
+        let b = vec![3, 0, 0];
+        assert_eq!(multiply_polynomials_trimmed(&a, &b), vec![3, 6]);
+
+        let a = vec![0, 0];
+        let b = vec![4, 5];
+        assert_eq!(multiply_polynomials_trimmed(&a, &b), vec![0]);
+
+        let a = vec![1, 2];
+        let b = vec![3, 4];
+        assert_eq!(multiply_polynomials_trimmed(&a, &b), vec![3, 10, 8]);
+    }
+
+    #[test]
+    fn subset_sum_counts() {
+        use super::subset_sum_counts;
+
+        let naive = |weights: &[u32], target: u32| {
+            let mut counts = vec![0u64; target as usize + 1];
+            counts[0] = 1;
+            for &w in weights {
+                for s in (w as usize..counts.len()).rev() {
+                    counts[s] = counts[s].wrapping_add(counts[s - w as usize]);
+                }
+            }
+            counts
+        };
+
+        let mut state = 2463534242u32;
+        let mut next = |bound: u32| {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state % bound
+        };
+        // Many small weights, so that the counts wrap around 2^64
+        // 2000 weights up to 25 give products of length 8192 and more from
+        // target 5000 on, where rounding errors used to creep in
+        for (n, max_weight, target) in [
+            (0, 1, 5),
+            (5, 10, 20),
+            (40, 30, 300),
+            (300, 50, 2000),
+            (2000, 26, 5000),
+            (2000, 26, 20000),
+        ] {
+            let weights = (0..n).map(|_| next(max_weight)).collect::<Vec<_>>();
+            assert_eq!(subset_sum_counts(&weights, target), naive(&weights, target));
+        }
+        // Splitting whenever possible, so that the products go through the NTT
+        for (n, max_weight, target) in [(1, 5, 10), (300, 50, 2000), (2000, 26, 20000)] {
+            let mut weights = (0..n).map(|_| next(max_weight)).collect::<Vec<_>>();
+            weights.sort_unstable();
+            let len = target as usize + 1;
+            let mut counts = super::subset_sum_product(&weights, len, 0);
+            counts.resize(len, 0);
+            assert_eq!(counts, naive(&weights, target));
+        }
+        // Weights close to the largest target, which made each factor take
+        // as much memory as its weight, 6 GB in total
+        let weights = (0..200)
+            .map(|_| 4_000_000 + next(194_304))
+            .collect::<Vec<_>>();
+        let counts = subset_sum_counts(&weights, (1 << 22) - 1);
+        assert_eq!(counts.len(), 1 << 22);
+        assert_eq!(counts[0], 1);
+        assert_eq!(counts.iter().sum::<u64>(), 1 + weights.len() as u64);
+
+        // Zero weights double every count, and weights above the target do not
+        // change anything
+        assert_eq!(subset_sum_counts(&[0, 3, 100], 4), vec![2, 0, 0, 2, 0]);
+    }
+
+    #[test]
+    #[should_panic(expected = "more than 2^23")]
+    fn convolution_arbitrary_mod_too_long() {
+        let a = vec![1; (1 << 22) + 1];
+        super::convolution_arbitrary_mod(&a, &a, 1_000_000_007);
+    }
+
+    #[test]
+    fn multiply_base() {
+        use super::multiply_base;
+
+        // Converts little-endian digits in base 10^k to base 10.
+        fn to_decimal(digits: &[u32], k: usize) -> Vec<u32> {
+            let mut decimal = digits
+                .iter()
+                .flat_map(|&d| {
+                    (0..k).scan(d, |d, _| {
+                        let digit = *d % 10;
+                        *d /= 10;
+                        Some(digit)
+                    })
+                })
+                .collect::<Vec<_>>();
+            while decimal.len() > 1 && decimal.last() == Some(&0) {
+                decimal.pop();
+            }
+            decimal
+        }
+
+        let mut state = 2463534242u32;
+        let mut digit = || {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            state % 10
+        };
+        let a = (0..300).map(|_| digit()).collect::<Vec<_>>();
+        let b = (0..210).map(|_| digit()).collect::<Vec<_>>();
+
+        // Schoolbook multiplication with one carry pass per digit of `b`
+        let mut expected = vec![0; a.len() + b.len()];
+        for (j, &y) in b.iter().enumerate() {
+            let mut carry = 0;
+            for (i, &x) in a.iter().enumerate() {
+                let cur = expected[i + j] + x * y + carry;
+                expected[i + j] = cur % 10;
+                carry = cur / 10;
+            }
+            expected[a.len() + j] = carry;
+        }
+        while expected.len() > 1 && expected.last() == Some(&0) {
+            expected.pop();
+        }
+        assert_eq!(multiply_base(&a, &b, 10), expected);
+
+        // Groups base-10 digits into base-10^k limbs.
+        let group = |digits: &[u32], k: usize| {
+            digits
+                .chunks(k)
+                .map(|chunk| chunk.iter().rev().fold(0, |acc, &d| acc * 10 + d))
+                .collect::<Vec<_>>()
+        };
+        let product = multiply_base(&group(&a, 3), &group(&b, 3), 1000);
+        assert_eq!(to_decimal(&product, 3), expected);
+        let product = multiply_base(&group(&a, 9), &group(&b, 9), 1_000_000_000);
+        assert_eq!(to_decimal(&product, 9), expected);
+
+        assert_eq!(multiply_base(&[0, 0], &[5], 10), vec![0]);
+        assert_eq!(multiply_base(&[9, 9], &[9, 9], 10), vec![1, 0, 8, 9]);
+
+        // Two-limb numbers in base 2^32 - 1 against u128 products
+        let base = u64::from(u32::MAX);
+        let mut state = 88172645463325252u64;
+        let mut limb = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % base) as u32
+        };
+        for _ in 0..100 {
+            let x = [limb(), limb()];
+            let y = [limb(), limb()];
+            let value = |x: &[u32]| {
+                x.iter()
+                    .rev()
+                    .fold(0, |acc, &d| acc * u128::from(base) + u128::from(d))
+            };
+            let mut expected = Vec::new();
+            let mut product = value(&x) * value(&y);
+            while product > 0 || expected.is_empty() {
+                expected.push((product % u128::from(base)) as u32);
+                product /= u128::from(base);
+            }
+            assert_eq!(multiply_base(&x, &y, u32::MAX), expected);
+        }
+    }
 }
@@ -0,0 +1,317 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+/// A set of lines `y = kx + m` that answers the minimum value at any `x`, with
+/// lines inserted in any order. Both insertions and queries take O(log N)
+/// amortized.
+///
+/// Internally, the lines are negated to keep the upper envelope of `-kx - m`,
+/// with each line on the envelope knowing the last `x` where it is optimal.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull_trick::DynamicLineContainer;
+/// let mut lines = DynamicLineContainer::new();
+/// lines.insert(1, 0);
+/// lines.insert(-1, 4);
+/// assert_eq!(lines.query(0), 0);
+/// assert_eq!(lines.query(5), -1);
+/// lines.insert(0, 1);
+/// assert_eq!(lines.query(2), 1);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DynamicLineContainer {
+    /// Maps the slope of each line on the envelope to its intercept and the
+    /// last `x` where it is optimal.
+    lines: BTreeMap<i64, (i64, i64)>,
+    /// The pairs of the last `x` where a line is optimal and its slope.
+    ends: BTreeSet<(i64, i64)>,
+}
+
+impl DynamicLineContainer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Adds the line `y = kx + m`.
+    pub fn insert(&mut self, k: i64, m: i64) {
+        let (k, m) = (-k, -m);
+        if let Some(&(old_m, _)) = self.lines.get(&k) {
+            if old_m >= m {
+                return;
+            }
+            self.remove(k);
+        }
+        self.lines.insert(k, (m, i64::MAX));
+        self.ends.insert((i64::MAX, k));
+
+        // Remove the following lines that are now never optimal
+        while let Some(z) = self.next(k) {
+            if self.intersect(k, z) < self.lines[&z].1 {
+                break;
+            }
+            self.remove(z);
+        }
+        self.update_end(k);
+
+        let mut y = k;
+        if let Some(x) = self.prev(k) {
+            if self.intersect(x, k) >= self.lines[&k].1 {
+                // The new line is never optimal
+                self.remove(k);
+                self.update_end(x);
+                return;
+            }
+            self.update_end(x);
+            y = x;
+        }
+
+        // Remove the preceding lines that are now never optimal
+        while let Some(x) = self.prev(y) {
+            if self.lines[&x].1 < self.lines[&y].1 {
+                break;
+            }
+            self.remove(y);
+            self.update_end(x);
+            y = x;
+        }
+    }
+
+    /// Returns the minimum of `kx + m` over the lines inserted so far.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no line has been inserted.
+    pub fn query(&self, x: i64) -> i64 {
+        let &(_, k) = self
+            .ends
+            .range((x, i64::MIN)..)
+            .next()
+            .expect("no lines to query");
+        let (m, _) = self.lines[&k];
+        -(k * x + m)
+    }
+
+    fn prev(&self, k: i64) -> Option<i64> {
+        self.lines.range(..k).next_back().map(|(&k, _)| k)
+    }
+
+    fn next(&self, k: i64) -> Option<i64> {
+        self.lines.range(k + 1..).next().map(|(&k, _)| k)
+    }
+
+    fn remove(&mut self, k: i64) {
+        let (_, end) = self.lines.remove(&k).unwrap();
+        self.ends.remove(&(end, k));
+    }
+
+    /// Returns the last `x` where the line of slope `x_k` is at least the line
+    /// of the larger slope `y_k`.
+    fn intersect(&self, x_k: i64, y_k: i64) -> i64 {
+        let (x_m, _) = self.lines[&x_k];
+        let (y_m, _) = self.lines[&y_k];
+        let end = (i128::from(x_m) - i128::from(y_m)).div_euclid(i128::from(y_k) - i128::from(x_k));
+        end.clamp(i128::from(i64::MIN), i128::from(i64::MAX)) as i64
+    }
+
+    fn update_end(&mut self, k: i64) {
+        let end = match self.next(k) {
+            Some(next) => self.intersect(k, next),
+            None => i64::MAX,
+        };
+        let entry = self.lines.get_mut(&k).unwrap();
+        self.ends.remove(&(entry.1, k));
+        entry.1 = end;
+        self.ends.insert((end, k));
+    }
+}
+
+/// A Li Chao tree answering the minimum of `m * x + b` over the lines added so
+/// far, for integer `x` in a fixed range `[lo, hi]` given on creation. Lines
+/// can be added in any order. Both operations take O(log (hi - lo)), and nodes
+/// are created only where lines are stored.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull_trick::LiChaoTree;
+/// let mut tree = LiChaoTree::new(-10, 10);
+/// tree.add_line(1, 0);
+/// tree.add_line(-1, 4);
+/// assert_eq!(tree.query(0), 0);
+/// assert_eq!(tree.query(5), -1);
+/// ```
+#[derive(Clone, Debug)]
+pub struct LiChaoTree {
+    lo: i64,
+    hi: i64,
+    nodes: Vec<LiChaoNode>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct LiChaoNode {
+    line: (i64, i64),
+    children: [usize; 2],
+}
+
+impl LiChaoTree {
+    /// Creates an empty tree for queries in `[lo, hi]`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `lo > hi`.
+    pub fn new(lo: i64, hi: i64) -> Self {
+        assert!(lo <= hi, "empty range [{}, {}]", lo, hi);
+        Self {
+            lo,
+            hi,
+            nodes: Vec::new(),
+        }
+    }
+
+    /// Adds the line `y = m * x + b`.
+    pub fn add_line(&mut self, m: i64, b: i64) {
+        let eval = |(m, b): (i64, i64), x: i64| m * x + b;
+        if self.nodes.is_empty() {
+            self.nodes.push(LiChaoNode {
+                line: (m, b),
+                children: [usize::MAX; 2],
+            });
+            return;
+        }
+
+        let mut line = (m, b);
+        let (mut node, mut lo, mut hi) = (0, self.lo, self.hi);
+        loop {
+            let mid = lo + (hi - lo) / 2;
+            let current = &mut self.nodes[node].line;
+            if eval(line, mid) < eval(*current, mid) {
+                std::mem::swap(&mut line, current);
+            }
+            if lo == hi {
+                return;
+            }
+
+            // The line that lost at `mid` can still win on one side only
+            let side = if eval(line, lo) < eval(*current, lo) {
+                0
+            } else if eval(line, hi) < eval(*current, hi) {
+                1
+            } else {
+                return;
+            };
+            if side == 0 {
+                hi = mid;
+            } else {
+                lo = mid + 1;
+            }
+            let child = self.nodes[node].children[side];
+            if child == usize::MAX {
+                self.nodes[node].children[side] = self.nodes.len();
+                self.nodes.push(LiChaoNode {
+                    line,
+                    children: [usize::MAX; 2],
+                });
+                return;
+            }
+            node = child;
+        }
+    }
+
+    /// Returns the minimum of `m * x + b` over the lines added so far, or
+    /// `i64::MAX` if there are none.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `x` is out of the range of the tree.
+    pub fn query(&self, x: i64) -> i64 {
+        assert!(
+            self.lo <= x && x <= self.hi,
+            "{} is out of [{}, {}]",
+            x,
+            self.lo,
+            self.hi
+        );
+        let mut min = i64::MAX;
+        let (mut node, mut lo, mut hi) = (0, self.lo, self.hi);
+        while let Some(&LiChaoNode { line, children }) = self.nodes.get(node) {
+            min = min.min(line.0 * x + line.1);
+            let mid = lo + (hi - lo) / 2;
+            if x <= mid {
+                node = children[0];
+                hi = mid;
+            } else {
+                node = children[1];
+                lo = mid + 1;
+            }
+        }
+        min
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DynamicLineContainer, LiChaoTree};
+
+    fn xorshift(state: &mut u64) -> u64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        *state
+    }
+
+    #[test]
+    fn dynamic_line_container() {
+        let mut state = 88172645463325252;
+        for _ in 0..100 {
+            let mut container = DynamicLineContainer::new();
+            let mut lines = Vec::new();
+            for _ in 0..50 {
+                let k = (xorshift(&mut state) % 41) as i64 - 20;
+                let m = (xorshift(&mut state) % 2001) as i64 - 1000;
+                container.insert(k, m);
+                lines.push((k, m));
+                for x in -30..=30 {
+                    let expected = lines.iter().map(|&(k, m)| k * x + m).min().unwrap();
+                    assert_eq!(container.query(x), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn li_chao_tree() {
+        let mut state = 2463534242;
+        for &(lo, hi) in &[(0, 0), (-30, 30), (-7, 100)] {
+            let mut tree = LiChaoTree::new(lo, hi);
+            assert_eq!(tree.query(lo), i64::MAX);
+            let mut lines = Vec::new();
+            for _ in 0..100 {
+                let m = (xorshift(&mut state) % 41) as i64 - 20;
+                let b = (xorshift(&mut state) % 2001) as i64 - 1000;
+                tree.add_line(m, b);
+                lines.push((m, b));
+                for x in lo..=hi {
+                    let expected = lines.iter().map(|&(m, b)| m * x + b).min().unwrap();
+                    assert_eq!(tree.query(x), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "out of")]
+    fn li_chao_tree_out_of_range() {
+        LiChaoTree::new(0, 10).query(11);
+    }
+
+    #[test]
+    #[should_panic(expected = "no lines")]
+    fn query_empty() {
+        DynamicLineContainer::new().query(0);
+    }
+}
@@ -1,3 +1,5 @@
+use std::thread;
+
 pub trait Context {
     fn l(&self) -> usize;
     fn r(&self) -> usize;
@@ -49,22 +51,102 @@ fn hilbert_order(x: usize, y: usize, pow: usize, rotate: usize) -> usize {
 
 /// Applies Mo's algorithm to the given queries. `B` is the block size, and `L`
 /// is the log of the maximum value of the queries.
+///
+/// Each query `(l, r)` is the inclusive range `[l, r]`, and `ctx` maintains the
+/// answer for the inclusive range `[ctx.l(), ctx.r()]`.
+///
+/// Both `l` and `r` must be less than `2^L`, since the queries are ordered
+/// along a Hilbert curve over a `2^L` by `2^L` grid. Larger values still give
+/// correct answers but in a far worse order, so this is checked in debug
+/// builds.
 pub fn apply<C: Context, const B: usize, const L: usize>(
     queries: &[(usize, usize)],
     ctx: &mut C,
 ) -> Vec<usize> {
+    let mut ans = vec![0; queries.len()];
+    apply_with_commit::<C, B, L>(queries, ctx, |idx, answer| ans[idx] = answer);
+    ans
+}
+
+/// Same as `apply`, but calls `commit` with the index of each query and its
+/// answer as soon as the query is answered, in processing order, instead of
+/// collecting the answers.
+pub fn apply_with_commit<C: Context, const B: usize, const L: usize>(
+    queries: &[(usize, usize)],
+    ctx: &mut C,
+    mut commit: impl FnMut(usize, usize),
+) {
+    let queries = hilbert_sorted::<L>(queries);
+    answer_sorted(&queries, ctx, |q, answer| commit(q.idx, answer));
+}
+
+/// Same as `apply`, but splits the queries, in the order they would be
+/// answered, into `threads` contiguous chunks answered on separate threads.
+///
+/// Each thread starts from its own clone of `ctx`, which therefore has to be
+/// in a state from which any query can be reached by extending and shrinking,
+/// as a freshly created context is. `ctx` itself is left unchanged.
+pub fn apply_parallel<C, const B: usize, const L: usize>(
+    queries: &[(usize, usize)],
+    ctx: &C,
+    threads: usize,
+) -> Vec<usize>
+where
+    C: Context + Clone + Send,
+{
+    let queries = hilbert_sorted::<L>(queries);
+    let chunk_size = queries.len().div_ceil(threads.max(1)).max(1);
+    let answers = thread::scope(|scope| {
+        let handles = queries
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let mut ctx = ctx.clone();
+                scope.spawn(move || {
+                    let mut answers = Vec::with_capacity(chunk.len());
+                    answer_sorted(chunk, &mut ctx, |_, answer| answers.push(answer));
+                    answers
+                })
+            })
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    let mut ans = vec![0; queries.len()];
+    for (q, answer) in queries.iter().zip(answers) {
+        ans[q.idx] = answer;
+    }
+    ans
+}
+
+fn hilbert_sorted<const L: usize>(queries: &[(usize, usize)]) -> Vec<Query> {
     let mut queries = queries
         .iter()
         .enumerate()
-        .map(|(idx, &(l, r))| Query {
-            l,
-            r,
-            idx,
-            ord: hilbert_order(l, r, L, 0),
+        .map(|(idx, &(l, r))| {
+            debug_assert!(
+                l.max(r) < 1 << L,
+                "query ({}, {}) does not fit in 2^{}; increase L",
+                l,
+                r,
+                L
+            );
+            Query {
+                l,
+                r,
+                idx,
+                ord: hilbert_order(l, r, L, 0),
+            }
         })
         .collect::<Vec<_>>();
     queries.sort_unstable_by_key(|q| q.ord);
-    let mut ans = vec![0; queries.len()];
+    queries
+}
+
+/// Answers the queries in the given order, moving `ctx` from one to the next,
+/// and calls `each` with every query and its answer.
+fn answer_sorted<C: Context>(queries: &[Query], ctx: &mut C, mut each: impl FnMut(&Query, usize)) {
     for q in queries {
         while ctx.l() > q.l {
             ctx.extend_l();
@@ -78,7 +160,179 @@ pub fn apply<C: Context, const B: usize, const L: usize>(
         while ctx.r() > q.r {
             ctx.shrink_r();
         }
-        ans[q.idx] = ctx.answer();
+        each(q, ctx.answer());
+    }
+}
+
+/// Applies Mo's algorithm to the given half-open queries `[l, r)`. Each query
+/// is converted to the inclusive range `[l, r - 1]`, so `ctx` follows the same
+/// inclusive contract as in `apply`.
+///
+/// # Panics
+///
+/// Panics if a query is empty, i.e., `l >= r`.
+pub fn apply_half_open<C: Context, const B: usize, const L: usize>(
+    queries: &[(usize, usize)],
+    ctx: &mut C,
+) -> Vec<usize> {
+    let queries = queries
+        .iter()
+        .map(|&(l, r)| {
+            assert!(l < r, "empty query [{}, {})", l, r);
+            (l, r - 1)
+        })
+        .collect::<Vec<_>>();
+    apply::<C, B, L>(&queries, ctx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Context;
+
+    /// Counts distinct values in the inclusive range `[l, r]`.
+    #[derive(Clone)]
+    struct Distinct<'a> {
+        values: &'a [usize],
+        counts: Vec<usize>,
+        l: usize,
+        r: usize,
+        distinct: usize,
+    }
+
+    impl<'a> Distinct<'a> {
+        fn new(values: &'a [usize]) -> Self {
+            let mut counts = vec![0; values.iter().max().unwrap() + 1];
+            counts[values[0]] = 1;
+            Self {
+                values,
+                counts,
+                l: 0,
+                r: 0,
+                distinct: 1,
+            }
+        }
+
+        fn add(&mut self, i: usize) {
+            self.counts[self.values[i]] += 1;
+            if self.counts[self.values[i]] == 1 {
+                self.distinct += 1;
+            }
+        }
+
+        fn remove(&mut self, i: usize) {
+            self.counts[self.values[i]] -= 1;
+            if self.counts[self.values[i]] == 0 {
+                self.distinct -= 1;
+            }
+        }
+    }
+
+    impl Context for Distinct<'_> {
+        fn l(&self) -> usize {
+            self.l
+        }
+
+        fn r(&self) -> usize {
+            self.r
+        }
+
+        fn answer(&self) -> usize {
+            self.distinct
+        }
+
+        fn extend_l(&mut self) {
+            self.l -= 1;
+            self.add(self.l);
+        }
+
+        fn extend_r(&mut self) {
+            self.r += 1;
+            self.add(self.r);
+        }
+
+        fn shrink_l(&mut self) {
+            self.remove(self.l);
+            self.l += 1;
+        }
+
+        fn shrink_r(&mut self) {
+            self.remove(self.r);
+            self.r -= 1;
+        }
+    }
+
+    const VALUES: [usize; 8] = [1, 2, 1, 3, 2, 2, 4, 1];
+
+    #[test]
+    fn apply() {
+        let queries = [(0, 7), (2, 2), (1, 4), (4, 5), (3, 6)];
+        let answers = super::apply::<_, 2, 3>(&queries, &mut Distinct::new(&VALUES));
+        assert_eq!(answers, vec![4, 1, 3, 1, 3]);
+    }
+
+    #[test]
+    fn apply_with_commit() {
+        let queries = [(0, 7), (2, 2), (1, 4), (4, 5), (3, 6)];
+        let expected = [4, 1, 3, 1, 3];
+        let mut committed = Vec::new();
+        super::apply_with_commit::<_, 2, 3>(
+            &queries,
+            &mut Distinct::new(&VALUES),
+            |idx, answer| committed.push((idx, answer)),
+        );
+        assert_eq!(committed.len(), queries.len());
+        let mut seen = [false; 5];
+        for &(idx, answer) in &committed {
+            assert!(!seen[idx]);
+            seen[idx] = true;
+            assert_eq!(answer, expected[idx]);
+        }
+    }
+
+    #[test]
+    fn apply_parallel() {
+        let mut state = 88172645463325252u64;
+        let mut next = |bound: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % bound as u64) as usize
+        };
+        let values = (0..500).map(|_| next(50)).collect::<Vec<_>>();
+        let queries = (0..300)
+            .map(|_| {
+                let (a, b) = (next(values.len()), next(values.len()));
+                (a.min(b), a.max(b))
+            })
+            .collect::<Vec<_>>();
+
+        let ctx = Distinct::new(&values);
+        let expected = super::apply::<_, 16, 9>(&queries, &mut ctx.clone());
+        for threads in [1, 2, 3, 8, 1000] {
+            let answers = super::apply_parallel::<_, 16, 9>(&queries, &ctx, threads);
+            assert_eq!(answers, expected);
+        }
+        assert!(super::apply_parallel::<_, 16, 9>(&[], &ctx, 4).is_empty());
+    }
+
+    #[test]
+    fn apply_half_open() {
+        let queries = [(0, 8), (2, 3), (1, 5), (4, 6), (3, 7)];
+        let answers = super::apply_half_open::<_, 2, 3>(&queries, &mut Distinct::new(&VALUES));
+        assert_eq!(answers, vec![4, 1, 3, 1, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "empty query")]
+    fn apply_half_open_empty() {
+        super::apply_half_open::<_, 2, 3>(&[(3, 3)], &mut Distinct::new(&VALUES));
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "increase L")]
+    fn apply_undersized_l() {
+        // 7 does not fit in 2^2
+        super::apply::<_, 2, 2>(&[(0, 7)], &mut Distinct::new(&VALUES));
     }
-    ans
 }
@@ -6,6 +6,61 @@ use std::{
     ops::{Mul, Sub},
 };
 
+/// A point in the plane with coordinates of type `Coord`, so that the hull
+/// functions accept custom point types as well as `(x, y)` tuples.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::{convex_hull, counterclockwise, Point2};
+/// #[derive(Clone, Copy, Debug, PartialEq)]
+/// struct Point {
+///     x: i64,
+///     y: i64,
+/// }
+///
+/// impl Point2 for Point {
+///     type Coord = i64;
+///
+///     fn x(&self) -> i64 {
+///         self.x
+///     }
+///
+///     fn y(&self) -> i64 {
+///         self.y
+///     }
+/// }
+///
+/// let points = [(0, 0), (2, 0), (1, 1), (0, 2)].map(|(x, y)| Point { x, y });
+/// let hull = convex_hull(points.to_vec(), |p| (p.x, p.y), counterclockwise);
+/// assert_eq!(hull, vec![points[0], points[1], points[3]]);
+/// ```
+pub trait Point2 {
+    type Coord: Copy;
+
+    fn x(&self) -> Self::Coord;
+    fn y(&self) -> Self::Coord;
+}
+
+impl<C: Copy> Point2 for (C, C) {
+    type Coord = C;
+
+    fn x(&self) -> C {
+        self.0
+    }
+
+    fn y(&self) -> C {
+        self.1
+    }
+}
+
+fn same_point<P: Point2>(a: &P, b: &P) -> bool
+where
+    P::Coord: PartialEq,
+{
+    a.x() == b.x() && a.y() == b.y()
+}
+
 /// Finds the convex hull of a set of points.
 ///
 /// This function takes a vector of points, a key function for sorting the
@@ -14,7 +69,8 @@ use std::{
 ///
 /// # Arguments
 ///
-/// * `points` - A vector of points represented as tuples of i64 coordinates.
+/// * `points` - A vector of points, such as tuples of coordinates or any other
+///   type implementing `Point2`.
 /// * `key` - A function that takes a point and returns a key for sorting the
 ///   points.
 /// * `turn_direction` - A function that takes three points and returns a
@@ -23,6 +79,13 @@ use std::{
 ///   `clockwise_or_collinear`, `counterclockwise`, or
 ///   `counterclockwise_or_collinear`.
 ///
+/// Points with equal keys are ordered by their coordinates, so the result does
+/// not depend on the order of `points`.
+///
+/// Duplicate points are considered once. If only one distinct point is given,
+/// the hull is that point, and if all the points are collinear, the hull
+/// consists of the two extreme points in key order.
+///
 /// # Examples
 ///
 /// ```
@@ -31,19 +94,53 @@ use std::{
 /// let hull = convex_hull(points, |&(x, y)| (x, y), counterclockwise);
 /// assert_eq!(hull, vec![(0, 0), (4, 0), (3, 2), (2, 2)]);
 /// ```
-pub fn convex_hull<C, K: PartialOrd>(
-    mut points: Vec<(C, C)>,
-    key: fn(&(C, C)) -> K,
-    turn_direction: fn(&(C, C), &(C, C), &(C, C)) -> bool,
-) -> Vec<(C, C)>
+pub fn convex_hull<P, K: PartialOrd>(
+    points: Vec<P>,
+    key: fn(&P) -> K,
+    turn_direction: fn(&P, &P, &P) -> bool,
+) -> Vec<P>
+where
+    P: Point2 + Copy + 'static,
+    P::Coord: PartialOrd,
+{
+    convex_hull_by(points, key, turn_direction)
+}
+
+/// Same as `convex_hull`, but takes closures, e.g., turn direction predicates
+/// capturing a tolerance.
+fn convex_hull_by<P, K: PartialOrd>(
+    mut points: Vec<P>,
+    key: impl Fn(&P) -> K,
+    turn_direction: impl Fn(&P, &P, &P) -> bool,
+) -> Vec<P>
 where
-    C: Copy + 'static,
+    P: Point2 + Copy + 'static,
+    P::Coord: PartialOrd,
 {
-    points.sort_unstable_by(|a, b| key(a).partial_cmp(&key(b)).unwrap());
+    points.sort_unstable_by(|a, b| {
+        key(a)
+            .partial_cmp(&key(b))
+            .unwrap()
+            .then_with(|| (a.x(), a.y()).partial_cmp(&(b.x(), b.y())).unwrap())
+    });
+    points.dedup_by(|a, b| same_point(a, b));
+    if points.len() <= 1 {
+        return points;
+    }
 
-    let mut hull = half_hull(&points, turn_direction);
+    let mut hull = half_hull(&points, &turn_direction);
+    let upper = half_hull(points.iter().rev(), &turn_direction);
+    if hull.len() == upper.len()
+        && hull
+            .iter()
+            .zip(upper.iter().rev())
+            .all(|(a, b)| same_point(a, b))
+    {
+        // All the points are collinear
+        return vec![points[0], points[points.len() - 1]];
+    }
     hull.pop();
-    hull.extend(half_hull(points.iter().rev(), turn_direction));
+    hull.extend(upper);
     hull.pop();
 
     hull
@@ -68,6 +165,100 @@ where
     (points, hull)
 }
 
+/// Same as `convex_hull_counterclockwise`, but lists the hull in clockwise
+/// order, still starting from the smallest point.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::convex_hull_clockwise;
+/// let points = vec![(0, 0), (2, 0), (1, 1), (2, 2), (0, 2)];
+/// let (sorted, hull) = convex_hull_clockwise(points, false);
+/// let hull = hull.iter().map(|&i| sorted[i]).collect::<Vec<_>>();
+/// assert_eq!(hull, vec![(0, 0), (0, 2), (2, 2), (2, 0)]);
+/// ```
+pub fn convex_hull_clockwise<C>(
+    points: Vec<(C, C)>,
+    hull_include_midpoints: bool,
+) -> (Vec<(C, C)>, Vec<usize>)
+where
+    C: Copy + Default + PartialOrd + Sub<Output = C> + Mul<Output = C>,
+{
+    let (points, mut hull) = convex_hull_counterclockwise(points, hull_include_midpoints);
+    if let Some(rest) = hull.get_mut(1..) {
+        rest.reverse();
+    }
+    (points, hull)
+}
+
+/// Same as `convex_hull_counterclockwise`, but returns the coordinates of the
+/// hull vertices in counterclockwise order instead of indices into the sorted
+/// points.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::convex_hull_points;
+/// let points = vec![(2, 2), (0, 0), (1, 0), (2, 0), (1, 1), (0, 2)];
+/// assert_eq!(convex_hull_points(points.clone(), false), vec![(0, 0), (2, 0), (2, 2), (0, 2)]);
+/// assert_eq!(
+///     convex_hull_points(points, true),
+///     vec![(0, 0), (1, 0), (2, 0), (2, 2), (0, 2)]
+/// );
+/// ```
+pub fn convex_hull_points(points: Vec<(i64, i64)>, include_midpoints: bool) -> Vec<(i64, i64)> {
+    let (points, hull) = convex_hull_counterclockwise(points, include_midpoints);
+    hull.into_iter().map(|i| points[i]).collect()
+}
+
+/// Decomposes the points into convex layers by repeatedly removing all the
+/// points on the boundary of the convex hull until none remain. Returns the
+/// layers from the outermost, each in counterclockwise order starting from its
+/// smallest point, including the points in the middle of its edges.
+///
+/// Duplicate points are considered once. Each layer takes a full hull
+/// computation, so this runs in O(N^2 log N) in the worst case.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::convex_layers;
+/// let points = vec![(0, 0), (4, 0), (4, 4), (0, 4), (1, 1), (3, 1), (2, 3), (2, 2)];
+/// let layers = convex_layers(points);
+/// assert_eq!(
+///     layers,
+///     vec![
+///         vec![(0, 0), (4, 0), (4, 4), (0, 4)],
+///         vec![(1, 1), (3, 1), (2, 3)],
+///         vec![(2, 2)],
+///     ]
+/// );
+/// ```
+pub fn convex_layers(mut points: Vec<(i64, i64)>) -> Vec<Vec<(i64, i64)>> {
+    points.sort_unstable();
+    points.dedup();
+    let mut layers = Vec::new();
+    while points.len() > 1 {
+        let (sorted, hull) = convex_hull_counterclockwise(points, true);
+        let mut on_hull = vec![false; sorted.len()];
+        for &i in &hull {
+            on_hull[i] = true;
+        }
+        layers.push(hull.into_iter().map(|i| sorted[i]).collect());
+        points = sorted
+            .into_iter()
+            .zip(on_hull)
+            .filter_map(|(p, on_hull)| (!on_hull).then_some(p))
+            .collect();
+    }
+    if !points.is_empty() {
+        // The hull of a single point is empty, so the last point is its own
+        // layer
+        layers.push(points);
+    }
+    layers
+}
+
 fn convex_hull_sorted<C: PartialOrd>(
     sorted_points: &[(C, C)],
     turn_direction: fn(&(C, C), &(C, C), &(C, C)) -> bool,
@@ -82,7 +273,6 @@ fn convex_hull_sorted<C: PartialOrd>(
         turn_direction,
     );
 
-    half.pop();
     mem::swap(&mut hull, &mut half);
     convex_hull_sorted_half(
         sorted_points,
@@ -90,6 +280,12 @@ fn convex_hull_sorted<C: PartialOrd>(
         &mut half,
         turn_direction,
     );
+    if hull.len() > 1 && hull.iter().eq(half.iter().rev()) {
+        // All the points are collinear, and both halves walk the same run, so
+        // joining them would list the points between the ends twice
+        return hull;
+    }
+    hull.pop();
     half.pop();
     hull.extend(half);
 
@@ -123,7 +319,7 @@ fn convex_hull_sorted_half<C>(
     for i in iter {
         while half.len() >= 2 {
             let o = &points[*half.get(half.len() - 2).unwrap()];
-            let a = &points[*half.get(half.len() - 1).unwrap()];
+            let a = &points[*half.last().unwrap()];
             if turn_direction(o, a, &points[i]) {
                 break;
             }
@@ -134,42 +330,79 @@ fn convex_hull_sorted_half<C>(
 }
 
 /// Determines if three points make a clockwise turn.
-pub fn clockwise<C>(o: &(C, C), a: &(C, C), b: &(C, C)) -> bool
+pub fn clockwise<P: Point2>(o: &P, a: &P, b: &P) -> bool
 where
-    C: Copy + Default + Sub<Output = C> + Mul<Output = C> + PartialOrd,
+    P::Coord: Default + Sub<Output = P::Coord> + Mul<Output = P::Coord> + PartialOrd,
 {
-    cross_product(o, a, b) < C::default()
+    cross_product(o, a, b) < P::Coord::default()
 }
 
 /// Determines if three points make a clockwise turn or are collinear.
-pub fn clockwise_or_collinear<C>(o: &(C, C), a: &(C, C), b: &(C, C)) -> bool
+pub fn clockwise_or_collinear<P: Point2>(o: &P, a: &P, b: &P) -> bool
 where
-    C: Copy + Default + Sub<Output = C> + Mul<Output = C> + PartialOrd,
+    P::Coord: Default + Sub<Output = P::Coord> + Mul<Output = P::Coord> + PartialOrd,
 {
-    cross_product(o, a, b) <= C::default()
+    cross_product(o, a, b) <= P::Coord::default()
 }
 
 /// Determines if three points make a counterclockwise turn.
-pub fn counterclockwise<C>(o: &(C, C), a: &(C, C), b: &(C, C)) -> bool
+pub fn counterclockwise<P: Point2>(o: &P, a: &P, b: &P) -> bool
 where
-    C: Copy + Default + Sub<Output = C> + Mul<Output = C> + PartialOrd,
+    P::Coord: Default + Sub<Output = P::Coord> + Mul<Output = P::Coord> + PartialOrd,
 {
-    cross_product(o, a, b) > C::default()
+    cross_product(o, a, b) > P::Coord::default()
 }
 
 /// Determines if three points make a counterclockwise turn or are collinear.
-pub fn counterclockwise_or_collinear<C>(o: &(C, C), a: &(C, C), b: &(C, C)) -> bool
+pub fn counterclockwise_or_collinear<P: Point2>(o: &P, a: &P, b: &P) -> bool
 where
-    C: Copy + Default + Sub<Output = C> + Mul<Output = C> + PartialOrd,
+    P::Coord: Default + Sub<Output = P::Coord> + Mul<Output = P::Coord> + PartialOrd,
 {
-    cross_product(o, a, b) >= C::default()
+    cross_product(o, a, b) >= P::Coord::default()
+}
+
+/// Same as `counterclockwise`, but treats the points as collinear if the
+/// absolute value of their cross product, i.e., twice the area of the
+/// triangle they form, is at most `eps`.
+pub fn counterclockwise_eps<P: Point2<Coord = f64>>(o: &P, a: &P, b: &P, eps: f64) -> bool {
+    cross_product(o, a, b) > eps
+}
+
+/// Same as `clockwise`, but treats the points as collinear if the absolute
+/// value of their cross product, i.e., twice the area of the triangle they
+/// form, is at most `eps`.
+pub fn clockwise_eps<P: Point2<Coord = f64>>(o: &P, a: &P, b: &P, eps: f64) -> bool {
+    cross_product(o, a, b) < -eps
+}
+
+/// Same as `convex_hull` sorting the points by their coordinates and turning
+/// counterclockwise, but drops a point unless it makes a turn by a cross
+/// product larger than `eps`, so that points meant to be collinear but
+/// perturbed by rounding errors do not appear in the hull. The smallest and
+/// largest points are always kept, even if a vertex next to them lies only
+/// slightly inside.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::convex_hull_f64;
+/// let points = vec![(0.0, 0.0), (1.0, 1e-12), (2.0, 0.0), (1.0, 1.0)];
+/// let hull = convex_hull_f64(points, 1e-9);
+/// assert_eq!(hull, vec![(0.0, 0.0), (2.0, 0.0), (1.0, 1.0)]);
+/// ```
+pub fn convex_hull_f64(points: Vec<(f64, f64)>, eps: f64) -> Vec<(f64, f64)> {
+    convex_hull_by(
+        points,
+        |&p| p,
+        |o: &(f64, f64), a: &(f64, f64), b: &(f64, f64)| counterclockwise_eps(o, a, b, eps),
+    )
 }
 
-fn cross_product<C>(o: &(C, C), a: &(C, C), b: &(C, C)) -> C
+fn cross_product<P: Point2>(o: &P, a: &P, b: &P) -> P::Coord
 where
-    C: Copy + Sub<Output = C> + Mul<Output = C>,
+    P::Coord: Sub<Output = P::Coord> + Mul<Output = P::Coord>,
 {
-    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    (a.x() - o.x()) * (b.y() - o.y()) - (a.y() - o.y()) * (b.x() - o.x())
 }
 
 /// Computes the half hull of a set of points, returning the points in sorted
@@ -188,15 +421,12 @@ where
 /// let lower_hull = half_hull(&points, counterclockwise);
 /// assert_eq!(lower_hull, vec![(0, 0), (4, 0)]);
 /// ```
-pub fn half_hull<'a, C, I>(
-    points: I,
-    turn_direction: fn(&(C, C), &(C, C), &(C, C)) -> bool,
-) -> Vec<(C, C)>
+pub fn half_hull<'a, P, I>(points: I, turn_direction: impl Fn(&P, &P, &P) -> bool) -> Vec<P>
 where
-    C: Copy + 'static,
-    I: IntoIterator<Item = &'a (C, C)>,
+    P: Copy + 'static,
+    I: IntoIterator<Item = &'a P>,
 {
-    let mut hull: Vec<(C, C)> = Vec::new();
+    let mut hull: Vec<P> = Vec::new();
 
     for &b in points {
         while hull.len() >= 2 {
@@ -213,100 +443,1896 @@ where
     hull
 }
 
-pub fn antipodal_pairs(points: &Vec<(i64, i64)>) -> Vec<(usize, usize)> {
+/// Finds the lower hull of the points, returning the indices of its vertices
+/// in `points` from the smallest point to the largest one.
+///
+/// The points are sorted by x and then by y, and walked in increasing order
+/// keeping only the counterclockwise turns, so collinear points are dropped
+/// and duplicate points are considered once.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::lower_hull;
+/// let points = [(2, 2), (0, 0), (1, -1), (3, 0), (1, 1)];
+/// assert_eq!(lower_hull(&points), vec![1, 2, 3]);
+/// ```
+pub fn lower_hull(points: &[(i64, i64)]) -> Vec<usize> {
+    sorted_half_hull(points, counterclockwise)
+}
+
+/// Finds the upper hull of the points, returning the indices of its vertices
+/// in `points` from the smallest point to the largest one.
+///
+/// The points are sorted by x and then by y, and walked in increasing order
+/// keeping only the clockwise turns, so collinear points are dropped and
+/// duplicate points are considered once.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::upper_hull;
+/// let points = [(2, 2), (0, 0), (1, -1), (3, 0), (1, 1)];
+/// assert_eq!(upper_hull(&points), vec![1, 0, 3]);
+/// ```
+pub fn upper_hull(points: &[(i64, i64)]) -> Vec<usize> {
+    sorted_half_hull(points, clockwise)
+}
+
+fn sorted_half_hull(
+    points: &[(i64, i64)],
+    turn_direction: fn(&(i64, i64), &(i64, i64), &(i64, i64)) -> bool,
+) -> Vec<usize> {
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_unstable_by_key(|&i| (points[i], i));
+    order.dedup_by_key(|&mut i| points[i]);
+    half_hull(&order, |&o: &usize, &a: &usize, &b: &usize| {
+        turn_direction(&points[o], &points[a], &points[b])
+    })
+}
+
+/// Returns an iterator over the edges of a polygon as pairs of consecutive
+/// vertices, including the edge from the last vertex back to the first.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::hull_edges;
+/// let hull = vec![(0, 0), (1, 0), (0, 1)];
+/// let edges = hull_edges(&hull).collect::<Vec<_>>();
+/// assert_eq!(edges, vec![(&(0, 0), &(1, 0)), (&(1, 0), &(0, 1)), (&(0, 1), &(0, 0))]);
+/// ```
+pub fn hull_edges<C>(hull: &[(C, C)]) -> impl Iterator<Item = (&(C, C), &(C, C))> {
+    hull.iter().zip(hull.iter().cycle().skip(1))
+}
+
+/// Computes twice the area of a convex hull whose vertices are in
+/// counterclockwise order, as returned by `convex_hull`, with the shoelace
+/// formula. Twice the area is always an integer, and the sum is accumulated in
+/// `i128` so that it cannot overflow.
+///
+/// The vertices must form a simple polygon; otherwise the result is
+/// meaningless.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::hull_area2;
+/// assert_eq!(hull_area2(&[(0, 0), (3, 0), (0, 1)]), 3);
+/// ```
+pub fn hull_area2(hull: &[(i64, i64)]) -> i128 {
+    hull_edges(hull)
+        .map(|(&a, &b)| {
+            let (a, b) = (widen(a), widen(b));
+            a.0 * b.1 - a.1 * b.0
+        })
+        .sum()
+}
+
+/// Computes the perimeter of a convex hull, including the edge from the last
+/// vertex back to the first. A hull of two points, as returned for collinear
+/// points, has twice their distance as its perimeter.
+///
+/// The vertices must form a simple polygon; otherwise the result is
+/// meaningless.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::hull_perimeter;
+/// assert_eq!(hull_perimeter(&[(0.0, 0.0), (3.0, 0.0), (0.0, 4.0)]), 12.0);
+/// ```
+pub fn hull_perimeter(hull: &[(f64, f64)]) -> f64 {
+    hull_edges(hull)
+        .map(|(a, b)| (b.0 - a.0).hypot(b.1 - a.1))
+        .sum()
+}
+
+/// Computes the Minkowski sum of two convex polygons whose vertices are in
+/// counterclockwise order, in O(N + M). The result is in counterclockwise
+/// order, starting from its lowest vertex, the leftmost one among ties.
+///
+/// Each polygon is first rotated to start from its lowest-then-leftmost
+/// vertex, and the edge vectors of both are merged in order of their angles.
+/// Parallel edges pointing the same way are merged into one, so the result has
+/// no collinear vertices unless an input does.
+///
+/// An input with fewer than 3 vertices is treated as a point or a segment, and
+/// the result is then that point or segment swept over the other polygon,
+/// which may be degenerate as well. If either input is empty, so is the
+/// result.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::minkowski_sum;
+/// let square = [(0, 0), (1, 0), (1, 1), (0, 1)];
+/// let segment = [(0, 0), (2, 2)];
+/// let sum = minkowski_sum(&square, &segment);
+/// assert_eq!(sum, vec![(0, 0), (1, 0), (3, 2), (3, 3), (2, 3), (0, 1)]);
+/// ```
+pub fn minkowski_sum(a: &[(i64, i64)], b: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    if a.is_empty() || b.is_empty() {
+        return vec![];
+    }
+    let (p, a_edges) = edge_vectors(a);
+    let (q, b_edges) = edge_vectors(b);
+
+    let mut current = (p.0 + q.0, p.1 + q.1);
+    let mut sum = vec![current];
+    let (mut i, mut j) = (0, 0);
+    while i < a_edges.len() || j < b_edges.len() {
+        let ord = match (a_edges.get(i), b_edges.get(j)) {
+            (Some(&u), Some(&v)) => compare_angles(u, v),
+            (Some(_), None) => Ordering::Less,
+            _ => Ordering::Greater,
+        };
+        if ord != Ordering::Greater {
+            current = (current.0 + a_edges[i].0, current.1 + a_edges[i].1);
+            i += 1;
+        }
+        if ord != Ordering::Less {
+            current = (current.0 + b_edges[j].0, current.1 + b_edges[j].1);
+            j += 1;
+        }
+        sum.push(current);
+    }
+    if sum.len() > 1 {
+        // The last edge leads back to the first vertex
+        sum.pop();
+    }
+    sum
+}
+
+/// Returns the lowest-then-leftmost vertex of a convex polygon and the
+/// nonzero edge vectors going counterclockwise from it.
+fn edge_vectors(polygon: &[(i64, i64)]) -> ((i64, i64), Vec<(i64, i64)>) {
+    let n = polygon.len();
+    let start = (0..n)
+        .min_by_key(|&i| (polygon[i].1, polygon[i].0))
+        .unwrap();
+    let edges = (start..start + n)
+        .map(|i| {
+            let (p, q) = (polygon[i % n], polygon[(i + 1) % n]);
+            (q.0 - p.0, q.1 - p.1)
+        })
+        .filter(|&e| e != (0, 0))
+        .collect();
+    (polygon[start], edges)
+}
+
+/// Compares the angles of two nonzero vectors, measured counterclockwise from
+/// the positive x-axis in `[0, 2π)`.
+fn compare_angles(u: (i64, i64), v: (i64, i64)) -> Ordering {
+    let lower_half = |(x, y): (i64, i64)| y < 0 || (y == 0 && x < 0);
+    lower_half(u).cmp(&lower_half(v)).then_with(|| {
+        let (u, v) = (widen(u), widen(v));
+        (v.0 * u.1).cmp(&(u.0 * v.1))
+    })
+}
+
+/// Checks if `points` form a convex polygon traversed counterclockwise, i.e.,
+/// every three consecutive vertices make a counterclockwise turn and the
+/// boundary goes around exactly once. Three consecutive collinear vertices are
+/// accepted only if `allow_collinear` is true.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::is_convex_ccw;
+/// assert!(is_convex_ccw(&[(0, 0), (1, 0), (1, 1), (0, 1)], false));
+/// assert!(!is_convex_ccw(&[(0, 0), (0, 1), (1, 1), (1, 0)], false));
+/// ```
+pub fn is_convex_ccw(points: &[(i64, i64)], allow_collinear: bool) -> bool {
     let n = points.len();
-    match n.cmp(&2) {
-        Ordering::Less => return vec![],
-        Ordering::Equal => return vec![(0, 1)],
-        _ => {}
+    if n < 3 {
+        return false;
     }
 
-    let mut pairs = Vec::new();
-    let mut j = 1;
+    let edge = |i: usize| {
+        let (p, q) = (points[i], points[(i + 1) % n]);
+        (q.0 - p.0, q.1 - p.1)
+    };
+    // Whether the angle of a vector is in [pi, 2 * pi).
+    let lower_half = |v: (i64, i64)| v.1 < 0 || v.1 == 0 && v.0 < 0;
 
+    let mut windings = 0;
     for i in 0..n {
-        let next_i = (i + 1) % n;
+        let (a, b) = (edge(i), edge((i + 1) % n));
+        if a == (0, 0) {
+            return false;
+        }
+        let cross = a.0 * b.1 - a.1 * b.0;
+        if cross < 0 || cross == 0 && (!allow_collinear || a.0 * b.0 + a.1 * b.1 < 0) {
+            return false;
+        }
+        if lower_half(a) && !lower_half(b) {
+            windings += 1;
+        }
+    }
+    windings == 1
+}
 
-        while {
-            let curr_area = cross_product(&points[i], &points[next_i], &points[j]);
-            let next_j = (j + 1) % n;
-            let next_area = cross_product(&points[i], &points[next_i], &points[next_j]);
-            next_area > curr_area
-        } {
-            j = (j + 1) % n;
+/// Removes the vertices of a polygon that are collinear with their neighbors,
+/// including around the seam between the last and the first vertices. The
+/// result has fewer than three vertices if the whole polygon is degenerate.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::remove_collinear;
+/// let polygon = vec![(0, 0), (1, 0), (2, 0), (2, 2), (0, 2), (0, 1)];
+/// assert_eq!(remove_collinear(&polygon), vec![(0, 0), (2, 0), (2, 2), (0, 2)]);
+/// ```
+pub fn remove_collinear(polygon: &[(i64, i64)]) -> Vec<(i64, i64)> {
+    let mut vertices: Vec<(i64, i64)> = Vec::new();
+    for p in polygon {
+        while vertices.len() >= 2
+            && cross_product(&vertices[vertices.len() - 2], vertices.last().unwrap(), p) == 0
+        {
+            vertices.pop();
+        }
+        vertices.push(*p);
+    }
+
+    let mut start = 0;
+    loop {
+        let n = vertices.len() - start;
+        if n < 3 {
+            break;
+        }
+        let (first, second) = (&vertices[start], &vertices[start + 1]);
+        let (second_last, last) = (&vertices[vertices.len() - 2], vertices.last().unwrap());
+        if cross_product(second_last, last, first) == 0 {
+            vertices.pop();
+        } else if cross_product(last, first, second) == 0 {
+            start += 1;
+        } else {
+            break;
         }
+    }
+    vertices.drain(..start);
+    vertices
+}
+
+/// Finds the antipodal pairs of a convex polygon whose vertices are in
+/// counterclockwise order, possibly including the points in the middle of its
+/// edges. For each edge from `i` to `i + 1`, returns `(i, j)` for the vertex
+/// `j` farthest from the line through the edge, so that every antipodal pair
+/// is `(i, j)` or `(i + 1, j)` for one of them.
+///
+/// If all the vertices are collinear, the only pair returned is the farthest
+/// pair `(i, j)` with `i < j`. Fewer than two vertices have no pairs.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::antipodal_pairs;
+/// let square = [(0, 0), (2, 0), (2, 2), (0, 2)];
+/// assert_eq!(antipodal_pairs(&square), vec![(0, 2), (1, 3), (2, 0), (3, 1)]);
+/// assert_eq!(antipodal_pairs(&[(1, 1), (0, 0), (2, 2)]), vec![(1, 2)]);
+/// ```
+pub fn antipodal_pairs(points: &[(i64, i64)]) -> Vec<(usize, usize)> {
+    let n = points.len();
+    if n < 2 {
+        return vec![];
+    }
+    if (2..n).all(|k| cross_product(&points[0], &points[1], &points[k]) == 0) {
+        // The extremes of points on a line are the smallest and largest ones
+        let (i, j) = (0..n).fold((0, 0), |(i, j), k| {
+            (
+                if points[k] < points[i] { k } else { i },
+                if points[k] > points[j] { k } else { j },
+            )
+        });
+        return vec![(i.min(j), i.max(j))];
+    }
 
+    let area = |i: usize, j: usize| cross_product(&points[i], &points[(i + 1) % n], &points[j]);
+    // Start from the farthest vertex of the first edge found by a full scan,
+    // as the points next to it may be collinear with it, where the area stays
+    // zero for a while before it starts growing
+    let mut j = (1..n).fold(1, |j, k| if area(0, k) > area(0, j) { k } else { j });
+    let mut pairs = Vec::with_capacity(n);
+    for i in 0..n {
+        while area(i, (j + 1) % n) > area(i, j) {
+            j = (j + 1) % n;
+        }
         pairs.push((i, j));
     }
 
     pairs
 }
 
-#[cfg(test)]
-mod tests {
-    #[test]
-    fn convex_hull() {
-        let points = vec![
-            (0, 0),
-            (1, 1),
-            (2, 2),
-            (3, 1),
-            (4, 0),
-            (3, -1),
-            (2, -2),
-            (1, -1),
-        ];
-
-        let hull = super::convex_hull(points, |&(x, y)| (x, y), super::counterclockwise);
-        assert_eq!(hull, &[(0, 0), (2, -2), (4, 0), (2, 2)]);
+/// Finds the farthest pair of points, returning their indices in `points` and
+/// their squared distance, or `None` if there are no points. The pair is found
+/// among the antipodal pairs of the convex hull in O(N log N), and a single
+/// point is paired with itself.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::diameter_pair;
+/// let points = [(0, 0), (1, 3), (4, 1), (2, 2)];
+/// assert_eq!(diameter_pair(&points), Some(((0, 2), 17)));
+/// ```
+pub fn diameter_pair(points: &[(i64, i64)]) -> Option<((usize, usize), i64)> {
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_unstable_by_key(|&i| points[i]);
+    let (sorted, hull) =
+        convex_hull_counterclockwise(order.iter().map(|&i| points[i]).collect(), false);
+    if sorted.first() == sorted.last() {
+        // All the points are at the same place
+        return order.first().map(|&i| ((i, i), 0));
     }
+    let hull_points = hull.iter().map(|&k| sorted[k]).collect::<Vec<_>>();
 
-    #[test]
-    fn partition() {
-        let points = vec![
-            (0, 0),
-            (1, 1),
-            (2, 2),
-            (3, 1),
-            (4, 0),
-            (3, -1),
-            (2, -2),
-            (1, -1),
-        ];
+    let distance2 = |p: (i64, i64), q: (i64, i64)| {
+        let (dx, dy) = (p.0 - q.0, p.1 - q.1);
+        dx * dx + dy * dy
+    };
+    antipodal_pairs(&hull_points)
+        .into_iter()
+        .flat_map(|(a, b)| [(a, b), ((a + 1) % hull.len(), b)])
+        .map(|(a, b)| (a, b, distance2(hull_points[a], hull_points[b])))
+        .max_by_key(|&(_, _, d)| d)
+        .map(|(a, b, d)| {
+            let (i, j) = (order[hull[a]], order[hull[b]]);
+            ((i.min(j), i.max(j)), d)
+        })
+}
 
-        let (_, hull) = super::convex_hull_counterclockwise(points, false);
-        assert_eq!(hull, &[0, 3, 7, 4]);
+/// Computes the largest squared distance between two of the points, which is
+/// zero if there are fewer than two points.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::diameter_squared;
+/// assert_eq!(diameter_squared(&[(0, 0), (1, 3), (4, 1), (2, 2)]), 17);
+/// assert_eq!(diameter_squared(&[(0, 0), (1, 1), (3, 3)]), 18);
+/// ```
+pub fn diameter_squared(points: &[(i64, i64)]) -> i64 {
+    diameter_pair(points).map_or(0, |(_, d)| d)
+}
+
+/// Checks if `p` lies in a convex polygon whose vertices are in
+/// counterclockwise order, in O(log N) by binary searching the fan of
+/// triangles around the first vertex. Points on the boundary count as inside
+/// unless `strict` is true. Polygons with fewer than three vertices contain
+/// the points on them only when `strict` is false. Vertices may be collinear,
+/// as in the hulls kept with their midpoints.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::contains_point;
+/// let hull = [(0, 0), (4, 0), (4, 4), (0, 4)];
+/// assert!(contains_point(&hull, (2, 2), true));
+/// assert!(contains_point(&hull, (4, 1), false));
+/// assert!(!contains_point(&hull, (4, 1), true));
+/// assert!(!contains_point(&hull, (5, 1), false));
+/// ```
+pub fn contains_point(hull: &[(i64, i64)], p: (i64, i64), strict: bool) -> bool {
+    let cross = |o: (i64, i64), a: (i64, i64), b: (i64, i64)| {
+        let (o, a, b) = (widen(o), widen(a), widen(b));
+        (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    };
+    let on_segment = |a: (i64, i64), b: (i64, i64)| {
+        cross(a, b, p) == 0
+            && a.0.min(b.0) <= p.0
+            && p.0 <= a.0.max(b.0)
+            && a.1.min(b.1) <= p.1
+            && p.1 <= a.1.max(b.1)
+    };
+
+    let n = hull.len();
+    if n < 3 {
+        return !strict && !hull.is_empty() && on_segment(hull[0], hull[n - 1]);
     }
 
-    #[test]
-    fn partition_last_pop() {
-        let points = vec![
-            (-5, 4),
-            (-4, 2),
-            (-3, 2),
-            (-2, -5),
-            (-1, 3),
-            (2, -5),
-            (2, -2),
-            (2, -1),
-        ];
-        let (_, hull) = super::convex_hull_counterclockwise(points, false);
-        assert_eq!(hull, &[0, 3, 5, 7, 4]);
+    let o = hull[0];
+    let (first, last) = (cross(o, hull[1], p), cross(o, hull[n - 1], p));
+    if first < 0 || last > 0 {
+        return false;
+    }
+    // On the ray through the second or the last vertex, the boundary runs to
+    // the farthest vertex on that ray, as the vertices next to it may be
+    // collinear with it; they form a prefix or a suffix of the fan
+    if first == 0 {
+        let k = hull[1..].partition_point(|&q| cross(o, hull[1], q) == 0);
+        return !strict && on_segment(o, hull[k]);
+    }
+    if last == 0 {
+        let k = hull[1..].partition_point(|&q| cross(o, hull[n - 1], q) != 0);
+        return !strict && on_segment(o, hull[k + 1]);
     }
 
-    #[test]
-    fn partition_middle_pop() {
-        let points = vec![
-            (-4, -5),
-            (-4, -3),
-            (-3, 4),
-            (1, -5),
-            (3, -2),
-            (3, -1),
-            (3, 4),
+    // Find the triangle (o, hull[i], hull[i + 1]) whose angle contains p
+    let (mut lo, mut hi) = (1, n - 1);
+    while hi - lo > 1 {
+        let mid = (lo + hi) / 2;
+        if cross(o, hull[mid], p) >= 0 {
+            lo = mid;
+        } else {
+            hi = mid;
+        }
+    }
+    let side = cross(hull[lo], hull[lo + 1], p);
+    side > 0 || side == 0 && !strict
+}
+
+/// A convex polygon whose vertices are in counterclockwise order, as returned
+/// by `convex_hull_counterclockwise`, answering point containment queries in
+/// O(log N) with `contains_point`. Degenerate hulls of one or two vertices
+/// contain the points on that point or segment, and collinear vertices, as
+/// kept by `convex_hull_counterclockwise` with midpoints, are allowed.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::ConvexPolygon;
+/// let polygon = ConvexPolygon::new(vec![(0, 0), (4, 0), (4, 4), (0, 4)]);
+/// assert!(polygon.contains((2, 2)));
+/// assert!(polygon.contains((4, 1)));
+/// assert!(!polygon.contains((5, 1)));
+/// ```
+#[derive(Clone, Debug)]
+pub struct ConvexPolygon {
+    vertices: Vec<(i64, i64)>,
+}
+
+impl ConvexPolygon {
+    pub fn new(hull: Vec<(i64, i64)>) -> Self {
+        Self { vertices: hull }
+    }
+
+    pub fn vertices(&self) -> &[(i64, i64)] {
+        &self.vertices
+    }
+
+    /// Checks if `p` lies in the polygon or on its boundary.
+    pub fn contains(&self, p: (i64, i64)) -> bool {
+        contains_point(&self.vertices, p, false)
+    }
+}
+
+/// Same as `ConvexPolygon`, but with floating-point coordinates, which also
+/// finds the closest point on its boundary in O(log N). No three vertices may
+/// be collinear.
+#[derive(Clone, Debug)]
+pub struct ConvexPolygonF64 {
+    vertices: Vec<(f64, f64)>,
+}
+
+impl ConvexPolygonF64 {
+    pub fn new(hull: Vec<(f64, f64)>) -> Self {
+        Self { vertices: hull }
+    }
+
+    pub fn vertices(&self) -> &[(f64, f64)] {
+        &self.vertices
+    }
+
+    /// Checks if `p` lies in the polygon or on its boundary in O(log N). The
+    /// polygon must have at least three vertices, no three of them collinear.
+    pub fn contains(&self, p: (f64, f64)) -> bool {
+        let n = self.vertices.len();
+        let k = self.edge_toward(p);
+        cross_product(&self.vertices[k], &self.vertices[(k + 1) % n], &p) >= 0.0
+    }
+
+    /// Finds the point on the boundary closest to `p`, or `p` itself if it
+    /// lies in the polygon, in O(log N). Polygons of one or two vertices are
+    /// treated as a point or a segment.
+    ///
+    /// The edge hit by the ray from the center towards `p` is visible from
+    /// `p`, and the one hit by the opposite ray is not, so the chain of edges
+    /// visible from `p`, which contains the closest point, is found by binary
+    /// searches between the two. Along that chain, `p` lies beyond the end of
+    /// the edges before the closest point and not of the rest.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the polygon has no vertices.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use convex_hull::ConvexPolygonF64;
+    /// let polygon = ConvexPolygonF64::new(vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+    /// assert_eq!(polygon.closest_boundary_point((2.0, -3.0)), (2.0, 0.0));
+    /// assert_eq!(polygon.closest_boundary_point((6.0, 7.0)), (4.0, 4.0));
+    /// assert_eq!(polygon.closest_boundary_point((1.0, 2.0)), (1.0, 2.0));
+    /// ```
+    pub fn closest_boundary_point(&self, p: (f64, f64)) -> (f64, f64) {
+        let vertices = &self.vertices;
+        let n = vertices.len();
+        assert!(n > 0, "empty polygon");
+        if n < 3 {
+            return closest_segment_point(vertices[0], vertices[n - 1], p);
+        }
+        if self.contains(p) {
+            return p;
+        }
+
+        let edge = |i: usize| (vertices[i % n], vertices[(i + 1) % n]);
+        let visible = |i: usize| {
+            let (a, b) = edge(i);
+            cross_product(&a, &b, &p) < 0.0
+        };
+        // The last `t` in `0..len` such that the edge `step(t)` is visible,
+        // given that the edge `step(0)` is and `step(len)` is not
+        let last_visible = |len: usize, step: &dyn Fn(usize) -> usize| {
+            let (mut lo, mut hi) = (0, len);
+            while hi - lo > 1 {
+                let mid = (lo + hi) / 2;
+                if visible(step(mid)) {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            lo
+        };
+
+        let center = self.center();
+        let k = self.edge_toward(p);
+        let f = self.edge_toward((2.0 * center.0 - p.0, 2.0 * center.1 - p.1));
+        let forward = last_visible((f + n - k) % n, &|t| k + t);
+        let backward = last_visible((k + n - f) % n, &|t| (k + n - t) % n);
+        let start = (k + n - backward) % n;
+        let len = backward + forward + 1;
+
+        // The first edge on the chain whose end `p` does not lie beyond
+        let (mut lo, mut hi) = (0, len);
+        while lo < hi {
+            let mid = (lo + hi) / 2;
+            let (a, b) = edge(start + mid);
+            if (p.0 - b.0) * (b.0 - a.0) + (p.1 - b.1) * (b.1 - a.1) > 0.0 {
+                lo = mid + 1;
+            } else {
+                hi = mid;
+            }
+        }
+        if lo == len {
+            return edge(start + len).0;
+        }
+        let (a, b) = edge(start + lo);
+        closest_segment_point(a, b, p)
+    }
+
+    /// Returns a point strictly inside the polygon.
+    fn center(&self) -> (f64, f64) {
+        let n = self.vertices.len();
+        let [a, b, c] = [0, n / 3, 2 * n / 3].map(|i| self.vertices[i]);
+        ((a.0 + b.0 + c.0) / 3.0, (a.1 + b.1 + c.1) / 3.0)
+    }
+
+    /// Finds the edge hit by the ray from the center through `p`, by binary
+    /// search on the angles of the vertices around the center.
+    fn edge_toward(&self, p: (f64, f64)) -> usize {
+        let center = self.center();
+        let direction = |q: (f64, f64)| (q.0 - center.0, q.1 - center.1);
+        let r = direction(self.vertices[0]);
+        let cross = |u: (f64, f64), v: (f64, f64)| u.0 * v.1 - u.1 * v.0;
+        // Whether the angle from `r` is at least π
+        let second_half = |u: (f64, f64)| {
+            let c = cross(r, u);
+            c < 0.0 || c == 0.0 && r.0 * u.0 + r.1 * u.1 < 0.0
+        };
+        let not_after = |u: (f64, f64), v: (f64, f64)| match (second_half(u), second_half(v)) {
+            (false, true) => true,
+            (true, false) => false,
+            _ => cross(u, v) >= 0.0,
+        };
+
+        let w = direction(p);
+        let (mut lo, mut hi) = (0, self.vertices.len());
+        while hi - lo > 1 {
+            let mid = (lo + hi) / 2;
+            if not_after(direction(self.vertices[mid]), w) {
+                lo = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        lo
+    }
+}
+
+/// Finds the point on the segment from `a` to `b` closest to `p`.
+fn closest_segment_point(a: (f64, f64), b: (f64, f64), p: (f64, f64)) -> (f64, f64) {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    if len2 == 0.0 {
+        return a;
+    }
+    let t = (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0);
+    (a.0 + t * dx, a.1 + t * dy)
+}
+
+/// Counts the points lying strictly inside a convex polygon whose vertices
+/// are in counterclockwise order, in O(M log N). Points on the boundary are
+/// not counted.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::count_interior_points;
+/// let hull = [(0, 0), (4, 0), (4, 4), (0, 4)];
+/// assert_eq!(count_interior_points(&hull, &[(1, 1), (0, 2), (5, 5)]), 1);
+/// ```
+pub fn count_interior_points(hull: &[(i64, i64)], points: &[(i64, i64)]) -> usize {
+    points
+        .iter()
+        .filter(|&&p| contains_point(hull, p, true))
+        .count()
+}
+
+fn widen((x, y): (i64, i64)) -> (i128, i128) {
+    (i128::from(x), i128::from(y))
+}
+
+/// Computes twice the square of the minimum width of a convex polygon whose
+/// vertices are in counterclockwise order, that is, `2 * w * w` for the
+/// smallest distance `w` between two parallel lines enclosing the polygon, as
+/// an irreducible fraction `(numerator, denominator)` with a positive
+/// denominator.
+///
+/// The edge attaining the minimum width is found with exact integer
+/// arithmetic using rotating calipers, comparing the squared heights
+/// `cross^2 / length^2` as fractions with 256-bit products. Every coordinate
+/// has to be at most 2^30 in absolute value, so that the cross products fit in
+/// 63 bits. Collinear and repeated vertices are allowed, and polygons with
+/// fewer than three distinct vertices have width 0.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::integer_polygon_width2;
+/// let hull = [(0, 0), (4, 0), (4, 2), (0, 2)];
+/// assert_eq!(integer_polygon_width2(&hull), (8, 1));
+/// let triangle = [(0, 0), (4, 0), (0, 3)];
+/// assert_eq!(integer_polygon_width2(&triangle), (288, 25));
+/// ```
+pub fn integer_polygon_width2(hull: &[(i64, i64)]) -> (i128, i128) {
+    let mut hull = hull.iter().map(|&p| widen(p)).collect::<Vec<_>>();
+    hull.dedup();
+    while hull.len() > 1 && hull.first() == hull.last() {
+        hull.pop();
+    }
+    let n = hull.len();
+    if n < 3 {
+        return (0, 1);
+    }
+
+    // The squared height of the farthest vertex from edge i as a fraction
+    let mut best: Option<(u128, u128)> = None;
+    let area = |i: usize, k: usize| cross_product(&hull[i], &hull[(i + 1) % n], &hull[k]);
+    // Start from the farthest vertex of the first edge found by a full scan,
+    // as in `antipodal_pairs`, since the vertices after the edge may be
+    // collinear with it
+    let mut j = (1..n).fold(1, |j, k| if area(0, k) > area(0, j) { k } else { j });
+    for i in 0..n {
+        let next_i = (i + 1) % n;
+        while area(i, (j + 1) % n) > area(i, j) {
+            j = (j + 1) % n;
+        }
+        let (dx, dy) = (hull[next_i].0 - hull[i].0, hull[next_i].1 - hull[i].1);
+        let length2 = (dx * dx + dy * dy) as u128;
+        let height2 = (area(i, j).unsigned_abs().pow(2), length2);
+        if best.is_none_or(|(num, den)| mul_wide(height2.0, den) < mul_wide(num, height2.1)) {
+            best = Some(height2);
+        }
+    }
+    best.map_or((0, 1), |(num, den)| {
+        let (num, den) = (2 * num as i128, den as i128);
+        let (mut a, mut b) = (num, den);
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        (num / a, den / a)
+    })
+}
+
+/// Multiplies two `u128`s into a 256-bit product given as its high and low
+/// halves, which compare in the same order as the products.
+fn mul_wide(a: u128, b: u128) -> (u128, u128) {
+    let (a_hi, a_lo) = (a >> 64, a & u128::from(u64::MAX));
+    let (b_hi, b_lo) = (b >> 64, b & u128::from(u64::MAX));
+    let lo = a_lo * b_lo;
+    let mid1 = a_lo * b_hi;
+    let mid2 = a_hi * b_lo;
+    let (mid, mid_carry) = mid1.overflowing_add(mid2);
+    let (lo, lo_carry) = lo.overflowing_add(mid << 64);
+    let hi = a_hi * b_hi + (mid >> 64) + (u128::from(mid_carry) << 64) + u128::from(lo_carry);
+    (hi, lo)
+}
+
+/// Finds the enclosing rectangle of minimum perimeter for a convex polygon
+/// whose vertices are in counterclockwise order, using rotating calipers. One
+/// side of the rectangle always lies on an edge of the polygon. Returns the
+/// corners in counterclockwise order. Repeated vertices are merged.
+///
+/// # Panics
+///
+/// Panics if `hull` is empty.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::min_perimeter_rectangle;
+/// let hull = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)];
+/// let rectangle = min_perimeter_rectangle(&hull);
+/// assert_eq!(rectangle, [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)]);
+/// ```
+pub fn min_perimeter_rectangle(hull: &[(f64, f64)]) -> [(f64, f64); 4] {
+    assert!(!hull.is_empty(), "empty polygon");
+    min_rectangle_by(hull, |width, height| 2.0 * (width + height))
+}
+
+/// Finds the enclosing rectangle of minimum area for a set of points, not
+/// necessarily aligned with the axes, and returns its corners in
+/// counterclockwise order along with its area. The rectangle is found with
+/// rotating calipers over the edges of the convex hull in O(N log N), since one
+/// of its sides always lies on a hull edge.
+///
+/// No tolerance is used: the hull drops only exactly collinear points, so
+/// nearly collinear points stay as hull vertices. Their short edges are merely
+/// extra candidates and do not change the result. A single distinct point
+/// gives a degenerate rectangle at that point.
+///
+/// # Panics
+///
+/// Panics if `points` is empty.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::min_area_rectangle;
+/// let points = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0), (1.0, 0.5)];
+/// let (rectangle, area) = min_area_rectangle(&points);
+/// assert_eq!(rectangle, [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)]);
+/// assert_eq!(area, 2.0);
+/// ```
+pub fn min_area_rectangle(points: &[(f64, f64)]) -> ([(f64, f64); 4], f64) {
+    assert!(!points.is_empty(), "no points");
+    let (sorted, hull) = convex_hull_counterclockwise(points.to_vec(), false);
+    if hull.is_empty() {
+        return ([sorted[0]; 4], 0.0);
+    }
+    let hull = hull.iter().map(|&i| sorted[i]).collect::<Vec<_>>();
+    let rectangle = min_rectangle_by(&hull, |width, height| width * height);
+    let side = |a: (f64, f64), b: (f64, f64)| (b.0 - a.0).hypot(b.1 - a.1);
+    let area = side(rectangle[0], rectangle[1]) * side(rectangle[1], rectangle[2]);
+    (rectangle, area)
+}
+
+/// Computes, for each edge of a convex polygon whose vertices are in
+/// counterclockwise order, the `(width, height)` of the enclosing rectangle
+/// with one side on that edge, using rotating calipers. The width is measured
+/// along the edge and the height perpendicular to it. Repeated consecutive
+/// vertices are merged, so a polygon with `n` distinct vertices yields `n`
+/// entries.
+///
+/// # Examples
+///
+/// ```
+/// # use convex_hull::caliper_profile;
+/// let hull = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)];
+/// let profile = caliper_profile(&hull);
+/// assert_eq!(profile, vec![(2.0, 1.0), (1.0, 2.0), (2.0, 1.0), (1.0, 2.0)]);
+/// ```
+pub fn caliper_profile(hull: &[(f64, f64)]) -> Vec<(f64, f64)> {
+    let mut profile = Vec::with_capacity(hull.len());
+    rotating_calipers(hull, |_, _, min_u, max_u, height| {
+        profile.push((max_u - min_u, height))
+    });
+    profile
+}
+
+/// Runs rotating calipers over the edges of a convex polygon in
+/// counterclockwise order and returns the enclosing rectangle, aligned with
+/// one of the edges, minimizing `key(width, height)`. `hull` must not be
+/// empty.
+fn min_rectangle_by(hull: &[(f64, f64)], key: fn(f64, f64) -> f64) -> [(f64, f64); 4] {
+    debug_assert!(!hull.is_empty());
+
+    let mut best: Option<(f64, [(f64, f64); 4])> = None;
+    rotating_calipers(hull, |p, u, min_u, max_u, height| {
+        let value = key(max_u - min_u, height);
+        if best.is_none_or(|(best_value, _)| value < best_value) {
+            let normal = (-u.1, u.0);
+            let corner =
+                |a: f64, b: f64| (p.0 + a * u.0 + b * normal.0, p.1 + a * u.1 + b * normal.1);
+            best = Some((
+                value,
+                [
+                    corner(min_u, 0.0),
+                    corner(max_u, 0.0),
+                    corner(max_u, height),
+                    corner(min_u, height),
+                ],
+            ));
+        }
+    });
+    // Without an edge of nonzero length, all the vertices are the same point
+    best.map_or_else(|| [hull[0]; 4], |(_, rectangle)| rectangle)
+}
+
+/// Calls `visit(p, u, min_u, max_u, height)` for each edge of nonzero length
+/// of a convex polygon in counterclockwise order, where `p` is the start of the
+/// edge and `u` its unit direction. The enclosing rectangle aligned with the
+/// edge spans `[min_u, max_u]` along `u` from `p` and `[0, height]` along the
+/// left normal of `u`.
+///
+/// Repeated vertices are merged first, since a pointer would stop on an edge
+/// of zero length, whose dot products with everything are zero.
+fn rotating_calipers(
+    hull: &[(f64, f64)],
+    mut visit: impl FnMut((f64, f64), (f64, f64), f64, f64, f64),
+) {
+    let mut hull = hull.to_vec();
+    hull.dedup();
+    while hull.len() > 1 && hull.first() == hull.last() {
+        hull.pop();
+    }
+    let n = hull.len();
+    if n < 2 {
+        return;
+    }
+    let dot = |a: (f64, f64), b: (f64, f64)| a.0 * b.0 + a.1 * b.1;
+    let sub = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0, a.1 - b.1);
+    let step = |k: usize| sub(hull[(k + 1) % n], hull[k]);
+
+    let (mut right, mut top, mut left) = (0, 0, 0);
+    for i in 0..n {
+        let (dx, dy) = step(i);
+        let length = dx.hypot(dy);
+        let u = (dx / length, dy / length);
+        let normal = (-u.1, u.0);
+
+        if i == 0 {
+            right = 0;
+        }
+        while dot(step(right), u) > 0.0 {
+            right = (right + 1) % n;
+        }
+        if i == 0 {
+            top = right;
+        }
+        while dot(step(top), normal) > 0.0 {
+            top = (top + 1) % n;
+        }
+        if i == 0 {
+            left = top;
+        }
+        while dot(step(left), u) < 0.0 {
+            left = (left + 1) % n;
+        }
+
+        let p = hull[i];
+        let min_u = dot(sub(hull[left], p), u);
+        let max_u = dot(sub(hull[right], p), u);
+        let height = dot(sub(hull[top], p), normal);
+        visit(p, u, min_u, max_u, height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn convex_hull_counterclockwise_collinear_run() {
+        use super::convex_hull_counterclockwise;
+
+        // Every point lies on the bottom edge
+        let points = vec![(3, 0), (0, 0), (1, 0), (2, 0)];
+        let (sorted, hull) = convex_hull_counterclockwise(points.clone(), true);
+        assert_eq!(sorted, vec![(0, 0), (1, 0), (2, 0), (3, 0)]);
+        assert_eq!(hull, vec![0, 1, 2, 3]);
+        let (_, hull) = convex_hull_counterclockwise(points, false);
+        assert_eq!(hull, vec![0, 3]);
+
+        // A collinear run along the bottom edge of a triangle
+        let points = vec![(0, 0), (1, 0), (2, 0), (3, 0), (0, 2)];
+        let (sorted, hull) = convex_hull_counterclockwise(points, true);
+        let hull = hull.iter().map(|&i| sorted[i]).collect::<Vec<_>>();
+        assert_eq!(hull, vec![(0, 0), (1, 0), (2, 0), (3, 0), (0, 2)]);
+    }
+
+    #[test]
+    fn convex_hull_equal_keys() {
+        use super::{convex_hull, counterclockwise};
+
+        // Sorting by x alone leaves the points on each vertical side in
+        // arbitrary order
+        let key = |&(x, _): &(i64, i64)| x;
+        let mut points = vec![
+            (0, 2),
+            (2, 1),
+            (0, 0),
+            (1, 1),
+            (2, 3),
+            (0, 1),
+            (2, 0),
+            (0, 3),
+            (2, 2),
+        ];
+        let expected = vec![(0, 0), (2, 0), (2, 3), (0, 3)];
+        for _ in 0..points.len() {
+            assert_eq!(convex_hull(points.clone(), key, counterclockwise), expected);
+            points.reverse();
+            assert_eq!(convex_hull(points.clone(), key, counterclockwise), expected);
+            points.rotate_left(1);
+        }
+    }
+
+    #[test]
+    fn hull_area_and_perimeter() {
+        use super::{convex_hull, counterclockwise, hull_area2, hull_perimeter};
+
+        let key = |&(x, y): &(i64, i64)| (x, y);
+        let to_f64 = |hull: &[(i64, i64)]| {
+            hull.iter()
+                .map(|&(x, y)| (x as f64, y as f64))
+                .collect::<Vec<_>>()
+        };
+
+        // Triangle
+        let hull = convex_hull(vec![(0, 0), (4, 0), (0, 3), (1, 1)], key, counterclockwise);
+        assert_eq!(hull_area2(&hull), 12);
+        assert_eq!(hull_perimeter(&to_f64(&hull)), 12.0);
+
+        // Square, whose doubled area overflows i64
+        let m = 1 << 30;
+        let hull = convex_hull(
+            vec![(-m, -m), (m, -m), (m, m), (-m, m), (0, 0)],
+            key,
+            counterclockwise,
+        );
+        assert_eq!(hull_area2(&hull), 2 * (2 * m as i128) * (2 * m as i128));
+        assert_eq!(hull_perimeter(&to_f64(&hull)), 8.0 * m as f64);
+
+        // Collinear points reduce to two points
+        let hull = convex_hull(vec![(0, 0), (2, 1), (4, 2), (6, 3)], key, counterclockwise);
+        assert_eq!(hull, vec![(0, 0), (6, 3)]);
+        assert_eq!(hull_area2(&hull), 0);
+        assert!((hull_perimeter(&to_f64(&hull)) - 2.0 * 45f64.sqrt()).abs() < 1e-12);
+
+        assert_eq!(hull_area2(&[(5, 5)]), 0);
+        assert_eq!(hull_perimeter(&[]), 0.0);
+    }
+
+    #[test]
+    fn diameter_pair() {
+        use super::{diameter_pair, diameter_squared};
+
+        let mut state = 88172645463325252u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 41) as i64 - 20
+        };
+        for n in 2..60 {
+            let points = (0..n).map(|_| (next(), next())).collect::<Vec<_>>();
+            let distance2 = |i: usize, j: usize| {
+                let (dx, dy) = (points[i].0 - points[j].0, points[i].1 - points[j].1);
+                dx * dx + dy * dy
+            };
+            let expected = (0..n)
+                .flat_map(|i| (0..n).map(move |j| (i, j)))
+                .map(|(i, j)| distance2(i, j))
+                .max()
+                .unwrap();
+            let ((i, j), d) = diameter_pair(&points).unwrap();
+            assert!(i <= j);
+            assert_eq!(d, expected);
+            assert_eq!(distance2(i, j), expected);
+            assert_eq!(diameter_squared(&points), expected);
+        }
+
+        // Degenerate inputs
+        assert_eq!(diameter_pair(&[]), None);
+        assert_eq!(diameter_squared(&[]), 0);
+        assert_eq!(diameter_pair(&[(3, 4)]), Some(((0, 0), 0)));
+        let ((i, j), d) = diameter_pair(&[(3, 4), (3, 4)]).unwrap();
+        assert!(i == j && i < 2 && d == 0);
+        assert_eq!(diameter_pair(&[(3, 4), (0, 0)]), Some(((0, 1), 25)));
+        let collinear = [(2, 2), (0, 0), (4, 4), (1, 1), (3, 3)];
+        assert_eq!(diameter_pair(&collinear), Some(((1, 2), 32)));
+    }
+
+    #[test]
+    fn convex_hull_degenerate() {
+        use super::{convex_hull, counterclockwise, counterclockwise_or_collinear};
+
+        let key = |&(x, y): &(i64, i64)| (x, y);
+        assert_eq!(convex_hull(vec![], key, counterclockwise), vec![]);
+        assert_eq!(
+            convex_hull(vec![(1, 2)], key, counterclockwise),
+            vec![(1, 2)]
+        );
+        let points = vec![(1, 2); 5];
+        assert_eq!(
+            convex_hull(points.clone(), key, counterclockwise),
+            vec![(1, 2)]
+        );
+        assert_eq!(
+            convex_hull(points, key, counterclockwise_or_collinear),
+            vec![(1, 2)]
+        );
+
+        let points = vec![(2, 2), (0, 0), (3, 3), (1, 1), (3, 3), (0, 0)];
+        assert_eq!(
+            convex_hull(points.clone(), key, counterclockwise),
+            vec![(0, 0), (3, 3)]
+        );
+        assert_eq!(
+            convex_hull(points, key, counterclockwise_or_collinear),
+            vec![(0, 0), (3, 3)]
+        );
+        assert_eq!(
+            convex_hull(vec![(0, 0), (0, 0), (1, 0)], key, counterclockwise),
+            vec![(0, 0), (1, 0)]
+        );
+    }
+
+    #[test]
+    fn convex_hull() {
+        let points = vec![
+            (0, 0),
+            (1, 1),
+            (2, 2),
+            (3, 1),
+            (4, 0),
+            (3, -1),
+            (2, -2),
+            (1, -1),
+        ];
+
+        let hull = super::convex_hull(points, |&(x, y)| (x, y), super::counterclockwise);
+        assert_eq!(hull, &[(0, 0), (2, -2), (4, 0), (2, 2)]);
+    }
+
+    #[test]
+    fn convex_hull_custom_point() {
+        use super::{clockwise, counterclockwise_or_collinear, Point2};
+
+        #[derive(Clone, Copy, Debug, PartialEq)]
+        struct Point {
+            x: f64,
+            y: f64,
+        }
+
+        impl Point2 for Point {
+            type Coord = f64;
+
+            fn x(&self) -> f64 {
+                self.x
+            }
+
+            fn y(&self) -> f64 {
+                self.y
+            }
+        }
+
+        let points = [
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 2.0),
+            (4.0, 0.0),
+            (2.0, -2.0),
+            (2.0, 0.0),
+        ]
+        .map(|(x, y)| Point { x, y });
+        let hull = super::convex_hull(points.to_vec(), |p| p.y, clockwise);
+        let expected = [(2.0, -2.0), (0.0, 0.0), (2.0, 2.0), (4.0, 0.0)];
+        assert_eq!(hull, expected.map(|(x, y)| Point { x, y }));
+
+        // The predicates agree with the tuple versions
+        for a in points {
+            for b in points {
+                let tuple = |p: Point| (p.x, p.y);
+                assert_eq!(
+                    counterclockwise_or_collinear(&points[0], &a, &b),
+                    counterclockwise_or_collinear(&tuple(points[0]), &tuple(a), &tuple(b))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn minkowski_sum() {
+        use super::{convex_hull, counterclockwise, minkowski_sum};
+
+        // Two triangles, the second pointing the other way
+        let a = [(0, 0), (1, 0), (0, 1)];
+        assert_eq!(minkowski_sum(&a, &a), vec![(0, 0), (2, 0), (0, 2)]);
+        let b = [(0, 0), (-1, 0), (0, -1)];
+        assert_eq!(
+            minkowski_sum(&a, &b),
+            vec![(0, -1), (1, -1), (1, 0), (0, 1), (-1, 1), (-1, 0)]
+        );
+
+        // A square plus a triangle, given from other starting vertices
+        let square = [(1, 1), (0, 1), (0, 0), (1, 0)];
+        let triangle = [(0, 1), (0, 0), (1, 0)];
+        assert_eq!(
+            minkowski_sum(&square, &triangle),
+            vec![(0, 0), (2, 0), (2, 1), (1, 2), (0, 2)]
+        );
+
+        // Degenerate inputs
+        assert_eq!(minkowski_sum(&a, &[(5, 5)]), vec![(5, 5), (6, 5), (5, 6)]);
+        assert_eq!(minkowski_sum(&[(1, 2)], &[(3, 4)]), vec![(4, 6)]);
+        assert_eq!(
+            minkowski_sum(&[(0, 0), (1, 1)], &[(2, 2), (0, 0)]),
+            vec![(0, 0), (3, 3)]
+        );
+        assert!(minkowski_sum(&a, &[]).is_empty());
+
+        // Compare with the hull of all pairwise sums
+        let mut state = 88172645463325252u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 21) as i64 - 10
+        };
+        let key = |&(x, y): &(i64, i64)| (y, x);
+        for _ in 0..200 {
+            let a = (0..8).map(|_| (next(), next())).collect::<Vec<_>>();
+            let b = (0..8).map(|_| (next(), next())).collect::<Vec<_>>();
+            let (a, b) = (
+                convex_hull(a, key, counterclockwise),
+                convex_hull(b, key, counterclockwise),
+            );
+            let sums = a
+                .iter()
+                .flat_map(|p| b.iter().map(move |q| (p.0 + q.0, p.1 + q.1)))
+                .collect();
+            assert_eq!(
+                minkowski_sum(&a, &b),
+                convex_hull(sums, key, counterclockwise)
+            );
+        }
+    }
+
+    #[test]
+    fn convex_hull_points() {
+        use super::{convex_hull_counterclockwise, convex_hull_points};
+
+        let mut state = 88172645463325252u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 11) as i64 - 5
+        };
+        for n in [1, 2, 3, 5, 10, 50] {
+            let points = (0..n).map(|_| (next(), next())).collect::<Vec<_>>();
+            for include_midpoints in [false, true] {
+                let (sorted, hull) =
+                    convex_hull_counterclockwise(points.clone(), include_midpoints);
+                let expected = hull.iter().map(|&i| sorted[i]).collect::<Vec<_>>();
+                assert_eq!(
+                    convex_hull_points(points.clone(), include_midpoints),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn convex_layers() {
+        use super::{convex_layers, is_convex_ccw};
+
+        // The layers of a square grid are the rings around its center
+        let grid = |n: i64| {
+            (0..n)
+                .flat_map(|x| (0..n).map(move |y| (x, y)))
+                .collect::<Vec<_>>()
+        };
+        let ring = |lo: i64, hi: i64| {
+            let mut ring = (lo..hi).map(|x| (x, lo)).collect::<Vec<_>>();
+            ring.extend((lo..hi).map(|y| (hi, y)));
+            ring.extend((lo + 1..=hi).rev().map(|x| (x, hi)));
+            ring.extend((lo + 1..=hi).rev().map(|y| (lo, y)));
+            ring
+        };
+        assert_eq!(
+            convex_layers(grid(5)),
+            vec![ring(0, 4), ring(1, 3), vec![(2, 2)]]
+        );
+        assert_eq!(convex_layers(grid(4)), vec![ring(0, 3), ring(1, 2)]);
+        assert_eq!(convex_layers(vec![(3, 3), (3, 3)]), vec![vec![(3, 3)]]);
+        assert!(convex_layers(vec![]).is_empty());
+
+        // Every layer is convex, lies inside the previous ones, and the layers
+        // partition the points
+        let mut state = 88172645463325252u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 21) as i64 - 10
+        };
+        for _ in 0..50 {
+            let mut points = (0..60).map(|_| (next(), next())).collect::<Vec<_>>();
+            let layers = convex_layers(points.clone());
+            for (i, layer) in layers.iter().enumerate() {
+                assert!(layer.len() < 3 || is_convex_ccw(layer, true));
+                for outer in &layers[..i] {
+                    if outer.len() >= 3 {
+                        for &p in layer {
+                            assert!(super::contains_point(outer, p, true));
+                        }
+                    }
+                }
+            }
+            let mut flattened = layers.concat();
+            flattened.sort_unstable();
+            points.sort_unstable();
+            points.dedup();
+            assert_eq!(flattened, points);
+        }
+    }
+
+    #[test]
+    fn antipodal_pairs() {
+        use super::antipodal_pairs;
+
+        // A collinear triple in either order has only its ends as a pair
+        assert_eq!(antipodal_pairs(&[(0, 0), (1, 0), (2, 0)]), vec![(0, 2)]);
+        assert_eq!(antipodal_pairs(&[(2, 2), (0, 0), (1, 1)]), vec![(0, 1)]);
+        assert_eq!(antipodal_pairs(&[(3, 1), (5, 2)]), vec![(0, 1)]);
+        assert!(antipodal_pairs(&[(3, 1)]).is_empty());
+        assert!(antipodal_pairs(&[]).is_empty());
+
+        // A thin quadrilateral, and the same one with points in the middle of
+        // its long edges, which used to keep the first edge paired with its
+        // own endpoint
+        let thin = [(0, 0), (100, 1), (101, 2), (1, 1)];
+        assert_eq!(antipodal_pairs(&thin), vec![(0, 2), (1, 3), (2, 0), (3, 1)]);
+        let thin = [(0, 0), (100, 1), (200, 2), (201, 3), (101, 2), (1, 1)];
+        let pairs = antipodal_pairs(&thin);
+        assert_eq!(pairs.len(), thin.len());
+        for (i, j) in pairs {
+            let farthest = (0..thin.len())
+                .map(|k| super::cross_product(&thin[i], &thin[(i + 1) % thin.len()], &thin[k]))
+                .max()
+                .unwrap();
+            assert_eq!(
+                super::cross_product(&thin[i], &thin[(i + 1) % thin.len()], &thin[j]),
+                farthest
+            );
+        }
+    }
+
+    #[test]
+    fn convex_hull_f64() {
+        use super::{
+            clockwise_eps, convex_hull, convex_hull_f64, counterclockwise, counterclockwise_eps,
+        };
+
+        // Points jittered off the edges of a triangle
+        let points = vec![
+            (0.0, 0.0),
+            (0.5, 1e-12),
+            (1.0, -1e-12),
+            (1.5, -2e-12),
+            (2.0, 0.0),
+            (1.5 + 1e-12, 0.5 + 1e-12),
+            (1.25 - 1e-12, 0.75),
+            (1.0, 1.0),
+            (0.5 - 1e-12, 0.5 + 1e-12),
+        ];
+        let triangle = vec![(0.0, 0.0), (2.0, 0.0), (1.0, 1.0)];
+        assert_eq!(convex_hull_f64(points.clone(), 1e-9), triangle);
+        // Without a tolerance, the jitter shows up in the hull
+        let exact = convex_hull(points, |&(x, y)| (x, y), counterclockwise);
+        assert!(exact.contains(&(1.5, -2e-12)) && exact.contains(&(1.5 + 1e-12, 0.5 + 1e-12)));
+
+        // Jittered points on a line leave only its ends
+        let line = (0..20)
+            .map(|i| {
+                let jitter = if i % 2 == 0 { 1e-12 } else { -1e-12 };
+                (i as f64 * 0.1, i as f64 * 0.2 + jitter)
+            })
+            .collect::<Vec<_>>();
+        let hull = convex_hull_f64(line.clone(), 1e-9);
+        assert_eq!(hull, vec![line[0], line[19]]);
+
+        let (o, a, b) = ((0.0, 0.0), (1.0, 0.0), (2.0, 1e-12));
+        assert!(!counterclockwise_eps(&o, &a, &b, 1e-9) && !clockwise_eps(&o, &a, &b, 1e-9));
+        assert!(counterclockwise_eps(&o, &a, &b, 0.0));
+        assert!(clockwise_eps(&o, &a, &(2.0, -1e-6), 1e-9));
+    }
+
+    #[test]
+    fn upper_and_lower_hull() {
+        use super::{convex_hull, counterclockwise, lower_hull, upper_hull};
+
+        let mut state = 88172645463325252u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 11) as i64 - 5
+        };
+        for n in [1, 2, 3, 5, 10, 50, 200] {
+            let points = (0..n).map(|_| (next(), next())).collect::<Vec<_>>();
+            let (lower, upper) = (lower_hull(&points), upper_hull(&points));
+            let hull = convex_hull(points.clone(), |&(x, y)| (x, y), counterclockwise);
+            assert_eq!(lower.first(), upper.first());
+            assert_eq!(lower.last(), upper.last());
+
+            // The lower hull followed by the upper hull backwards, without the
+            // shared ends, goes around the whole hull
+            let mut combined = lower.iter().map(|&i| points[i]).collect::<Vec<_>>();
+            if upper.len() > 2 {
+                combined.extend(upper[1..upper.len() - 1].iter().rev().map(|&i| points[i]));
+            }
+            if hull.len() == 1 {
+                combined.truncate(1);
+            }
+            assert_eq!(combined, hull);
+        }
+
+        // Collinear points are dropped, and duplicates are taken once
+        let points = [(0, 0), (1, 1), (2, 2), (0, 0), (2, 2)];
+        assert_eq!(lower_hull(&points), vec![0, 2]);
+        assert_eq!(upper_hull(&points), vec![0, 2]);
+        assert!(lower_hull(&[]).is_empty());
+    }
+
+    #[test]
+    fn partition() {
+        let points = vec![
+            (0, 0),
+            (1, 1),
+            (2, 2),
+            (3, 1),
+            (4, 0),
+            (3, -1),
+            (2, -2),
+            (1, -1),
+        ];
+
+        let (_, hull) = super::convex_hull_counterclockwise(points.clone(), false);
+        assert_eq!(hull, &[0, 3, 7, 4]);
+        let (_, hull) = super::convex_hull_clockwise(points, false);
+        assert_eq!(hull, &[0, 4, 7, 3]);
+    }
+
+    #[test]
+    fn convex_hull_clockwise() {
+        let points = vec![
+            (-4, -5),
+            (-4, -3),
+            (-3, 4),
+            (1, -5),
+            (3, -2),
+            (3, -1),
+            (3, 4),
+            (4, -5),
+            (0, -5),
+        ];
+        for include_midpoints in [false, true] {
+            let (sorted_ccw, ccw) =
+                super::convex_hull_counterclockwise(points.clone(), include_midpoints);
+            let (sorted_cw, cw) = super::convex_hull_clockwise(points.clone(), include_midpoints);
+            assert_eq!(sorted_cw, sorted_ccw);
+            let mut reversed = ccw.clone();
+            reversed.reverse();
+            reversed.rotate_right(1);
+            assert_eq!(cw, reversed);
+        }
+
+        let (_, hull) = super::convex_hull_clockwise(vec![(1, 1), (0, 0)], false);
+        assert_eq!(hull, &[0, 1]);
+        let (_, hull) = super::convex_hull_clockwise(Vec::<(i64, i64)>::new(), false);
+        assert!(hull.is_empty());
+    }
+
+    #[test]
+    fn partition_last_pop() {
+        let points = vec![
+            (-5, 4),
+            (-4, 2),
+            (-3, 2),
+            (-2, -5),
+            (-1, 3),
+            (2, -5),
+            (2, -2),
+            (2, -1),
+        ];
+        let (_, hull) = super::convex_hull_counterclockwise(points, false);
+        assert_eq!(hull, &[0, 3, 5, 7, 4]);
+    }
+
+    #[test]
+    fn partition_middle_pop() {
+        let points = vec![
+            (-4, -5),
+            (-4, -3),
+            (-3, 4),
+            (1, -5),
+            (3, -2),
+            (3, -1),
+            (3, 4),
             (4, -5),
         ];
         let (_sorted, hull) = super::convex_hull_counterclockwise(points, false);
         assert_eq!(hull, &[0, 7, 6, 2, 1]);
     }
+
+    #[test]
+    fn hull_edges() {
+        let hull = vec![(0, 0), (2, -2), (4, 0), (2, 2)];
+        let edges = super::hull_edges(&hull).collect::<Vec<_>>();
+        assert_eq!(edges.len(), hull.len());
+        assert_eq!(edges[3], (&(2, 2), &(0, 0)));
+        assert!(edges.windows(2).all(|w| w[0].1 == w[1].0));
+
+        assert_eq!(super::hull_edges::<i64>(&[]).count(), 0);
+    }
+
+    #[test]
+    fn is_convex_ccw() {
+        let square = [(0, 0), (2, 0), (2, 2), (0, 2)];
+        assert!(super::is_convex_ccw(&square, false));
+
+        let clockwise = [(0, 0), (0, 2), (2, 2), (2, 0)];
+        assert!(!super::is_convex_ccw(&clockwise, false));
+
+        let non_convex = [(0, 0), (2, 0), (1, 1), (2, 2), (0, 2)];
+        assert!(!super::is_convex_ccw(&non_convex, false));
+
+        let with_midpoint = [(0, 0), (1, 0), (2, 0), (2, 2), (0, 2)];
+        assert!(!super::is_convex_ccw(&with_midpoint, false));
+        assert!(super::is_convex_ccw(&with_midpoint, true));
+
+        let pentagram = [(0, 10), (-6, -8), (10, 3), (-10, 3), (6, -8)];
+        assert!(!super::is_convex_ccw(&pentagram, false));
+    }
+
+    #[test]
+    fn remove_collinear() {
+        let polygon = [
+            (1, 0),
+            (2, 0),
+            (2, 1),
+            (2, 2),
+            (1, 2),
+            (0, 2),
+            (0, 1),
+            (0, 0),
+        ];
+        assert_eq!(
+            super::remove_collinear(&polygon),
+            vec![(2, 0), (2, 2), (0, 2), (0, 0)]
+        );
+
+        let polygon = [(0, 0), (3, 0), (3, 1), (3, 2), (3, 3), (2, 2), (1, 1)];
+        assert_eq!(
+            super::remove_collinear(&polygon),
+            vec![(0, 0), (3, 0), (3, 3)]
+        );
+
+        let segment = [(0, 0), (1, 1), (2, 2)];
+        assert_eq!(super::remove_collinear(&segment), vec![(0, 0), (2, 2)]);
+    }
+
+    #[test]
+    fn contains_point() {
+        let hull = [(0, 0), (6, 0), (8, 4), (4, 8), (0, 5)];
+        let inside = [(1, 1), (4, 4), (7, 4), (1, 5), (5, 1), (2, 6)];
+        let boundary = [(0, 0), (3, 0), (7, 2), (6, 6), (4, 8), (0, 3), (8, 4)];
+        let outside = [(-1, 0), (7, 0), (9, 4), (4, 9), (0, 6), (3, -1), (8, 8)];
+        for &p in &inside {
+            assert!(super::contains_point(&hull, p, true), "{:?}", p);
+            assert!(super::contains_point(&hull, p, false), "{:?}", p);
+        }
+        for &p in &boundary {
+            assert!(!super::contains_point(&hull, p, true), "{:?}", p);
+            assert!(super::contains_point(&hull, p, false), "{:?}", p);
+        }
+        for &p in &outside {
+            assert!(!super::contains_point(&hull, p, true), "{:?}", p);
+            assert!(!super::contains_point(&hull, p, false), "{:?}", p);
+        }
+
+        let points = [&inside[..], &[(3, 0), (0, 9), (7, 2), (4, 4), (-1, -1)]].concat();
+        assert_eq!(super::count_interior_points(&hull, &points), 7);
+
+        // Collinear vertices along the first and the last edges
+        let hulls = [
+            [(0, 0), (1, 0), (2, 0), (3, 0), (3, 3), (0, 3)],
+            [(0, 0), (3, 0), (3, 3), (0, 3), (0, 2), (0, 1)],
+        ];
+        for hull in &hulls {
+            for p in [(1, 0), (2, 0), (3, 0), (0, 1), (0, 2), (0, 3), (3, 2)] {
+                assert!(super::contains_point(hull, p, false), "{:?}", p);
+                assert!(!super::contains_point(hull, p, true), "{:?}", p);
+            }
+            for p in [(4, 0), (-1, 0), (0, 4), (0, -1)] {
+                assert!(!super::contains_point(hull, p, false), "{:?}", p);
+            }
+            assert!(super::contains_point(hull, (1, 1), true));
+        }
+
+        let segment = [(0, 0), (2, 2)];
+        assert!(super::contains_point(&segment, (1, 1), false));
+        assert!(!super::contains_point(&segment, (1, 1), true));
+        assert!(!super::contains_point(&segment, (3, 3), false));
+        assert!(!super::contains_point(&[], (0, 0), false));
+    }
+
+    #[test]
+    fn convex_polygon() {
+        use super::{convex_hull_counterclockwise, ConvexPolygon};
+
+        let mut state = 88172645463325252u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 21) as i64 - 10
+        };
+        for n in 2..30 {
+            let mut points = (0..n).map(|_| (next(), next())).collect::<Vec<_>>();
+            points.sort_unstable();
+            points.dedup();
+            let (sorted, hull) = convex_hull_counterclockwise(points.clone(), false);
+            let vertices = hull.iter().map(|&i| sorted[i]).collect::<Vec<_>>();
+            let polygon = ConvexPolygon::new(vertices.clone());
+            assert_eq!(polygon.vertices(), &vertices[..]);
+            // The same polygon keeping the points on its edges as vertices
+            let (sorted, hull) = convex_hull_counterclockwise(points, true);
+            let with_midpoints = ConvexPolygon::new(hull.iter().map(|&i| sorted[i]).collect());
+
+            // Inside or on the boundary if no edge has it strictly on its right
+            let m = vertices.len();
+            for x in -11..=11 {
+                for y in -11..=11 {
+                    let expected = if m < 3 {
+                        let (a, b) = (vertices[0], vertices[m - 1]);
+                        (b.0 - a.0) * (y - a.1) == (b.1 - a.1) * (x - a.0)
+                            && a.0.min(b.0) <= x
+                            && x <= a.0.max(b.0)
+                            && a.1.min(b.1) <= y
+                            && y <= a.1.max(b.1)
+                    } else {
+                        (0..m).all(|i| {
+                            let (a, b) = (vertices[i], vertices[(i + 1) % m]);
+                            (b.0 - a.0) * (y - a.1) - (b.1 - a.1) * (x - a.0) >= 0
+                        })
+                    };
+                    assert_eq!(polygon.contains((x, y)), expected, "{:?}", (x, y));
+                    assert_eq!(with_midpoints.contains((x, y)), expected, "{:?}", (x, y));
+                }
+            }
+        }
+
+        // A single point and a segment, with coordinates near the i64 limits
+        let point = ConvexPolygon::new(vec![(3, -2)]);
+        assert!(point.contains((3, -2)));
+        assert!(!point.contains((3, -1)));
+        let m = i64::MAX / 2;
+        let segment = ConvexPolygon::new(vec![(-m, -m), (m, m)]);
+        assert!(segment.contains((0, 0)));
+        assert!(segment.contains((m, m)));
+        assert!(!segment.contains((m, m - 1)));
+        let square = ConvexPolygon::new(vec![(-m, -m), (m, -m), (m, m), (-m, m)]);
+        assert!(square.contains((m - 1, 1 - m)));
+        assert!(!square.contains((m + 1, 0)));
+        assert!(!ConvexPolygon::new(vec![]).contains((0, 0)));
+
+        // Boundary points past collinear vertices next to the first one
+        let polygon = ConvexPolygon::new(vec![(0, 0), (1, 0), (2, 0), (3, 0), (3, 3), (0, 3)]);
+        assert!(polygon.contains((2, 0)));
+        assert!(polygon.contains((3, 0)));
+        assert!(!polygon.contains((4, 0)));
+        let polygon = ConvexPolygon::new(vec![(0, 0), (3, 0), (3, 3), (0, 3), (0, 2), (0, 1)]);
+        assert!(polygon.contains((0, 2)));
+        assert!(!polygon.contains((0, -1)));
+    }
+
+    #[test]
+    fn closest_boundary_point() {
+        use super::{closest_segment_point, convex_hull, counterclockwise, ConvexPolygonF64};
+
+        let square = ConvexPolygonF64::new(vec![(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (0.0, 4.0)]);
+        // Directly outside an edge, nearest a vertex, inside, and on the
+        // boundary
+        assert_eq!(square.closest_boundary_point((5.0, 1.5)), (4.0, 1.5));
+        assert_eq!(square.closest_boundary_point((-1.0, -2.0)), (0.0, 0.0));
+        assert_eq!(square.closest_boundary_point((3.0, 1.0)), (3.0, 1.0));
+        assert_eq!(square.closest_boundary_point((0.0, 2.0)), (0.0, 2.0));
+        let segment = ConvexPolygonF64::new(vec![(0.0, 0.0), (2.0, 2.0)]);
+        assert_eq!(segment.closest_boundary_point((2.0, 0.0)), (1.0, 1.0));
+        let point = ConvexPolygonF64::new(vec![(1.0, 2.0)]);
+        assert_eq!(point.closest_boundary_point((5.0, 5.0)), (1.0, 2.0));
+
+        // Compare with the closest point over all the edges
+        let mut state = 88172645463325252u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 2001) as f64 / 100.0 - 10.0
+        };
+        let distance = |a: (f64, f64), b: (f64, f64)| (a.0 - b.0).hypot(a.1 - b.1);
+        for n in 3..40 {
+            let points = (0..n).map(|_| (next(), next())).collect::<Vec<_>>();
+            let hull = convex_hull(points, |&(x, y)| (x, y), counterclockwise);
+            if hull.len() < 3 {
+                continue;
+            }
+            let polygon = ConvexPolygonF64::new(hull.clone());
+            for _ in 0..200 {
+                let p = (2.0 * next(), 2.0 * next());
+                let q = polygon.closest_boundary_point(p);
+                let inside = (0..hull.len()).all(|i| {
+                    super::cross_product(&hull[i], &hull[(i + 1) % hull.len()], &p) >= 0.0
+                });
+                assert_eq!(polygon.contains(p), inside);
+                if inside {
+                    assert_eq!(q, p);
+                    continue;
+                }
+                let expected = (0..hull.len())
+                    .map(|i| {
+                        let c = closest_segment_point(hull[i], hull[(i + 1) % hull.len()], p);
+                        distance(c, p)
+                    })
+                    .fold(f64::INFINITY, f64::min);
+                assert!((distance(q, p) - expected).abs() < 1e-9, "{:?}", p);
+            }
+        }
+    }
+
+    #[test]
+    fn integer_polygon_width2() {
+        let rectangle = [(1, 1), (6, 1), (6, 4), (1, 4)];
+        assert_eq!(super::integer_polygon_width2(&rectangle), (18, 1));
+
+        // The width of this triangle is its height over the hypotenuse, 12 / 5
+        let triangle = [(0, 0), (4, 0), (0, 3)];
+        assert_eq!(super::integer_polygon_width2(&triangle), (288, 25));
+
+        // A diamond whose width 3 * sqrt(2) is attained along each edge
+        let diamond = [(3, 0), (6, 3), (3, 6), (0, 3)];
+        assert_eq!(super::integer_polygon_width2(&diamond), (36, 1));
+
+        // Squared heights times squared lengths overflow i128 here
+        let square = [
+            (0, 0),
+            (20_000_000, 1),
+            (20_000_000, 20_000_000),
+            (1, 20_000_000),
+        ];
+        assert_eq!(
+            super::integer_polygon_width2(&square),
+            (319_999_999_999_998_400_000_000_000_002, 400_000_000_000_001)
+        );
+        let c = 1 << 30;
+        let rectangle = [(-c, -c), (c, -c), (c, 0), (-c, 0)];
+        assert_eq!(super::integer_polygon_width2(&rectangle), (1 << 61, 1));
+
+        // Collinear vertices after the first edge, and a repeated first vertex
+        let hull = [(0, 0), (1, 0), (2, 0), (2, 1), (0, 1)];
+        assert_eq!(super::integer_polygon_width2(&hull), (2, 1));
+        let hull = [(0, 0), (0, 0), (1, 0), (2, 0), (2, 3), (0, 3)];
+        assert_eq!(super::integer_polygon_width2(&hull), (8, 1));
+
+        assert_eq!(super::integer_polygon_width2(&[(0, 0), (5, 5)]), (0, 1));
+
+        // Hulls keeping their collinear vertices against every edge
+        let mut state = 2463534242u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 7) as i64
+        };
+        for n in 3..40 {
+            let mut points = (0..n).map(|_| (next(), next())).collect::<Vec<_>>();
+            points.sort_unstable();
+            points.dedup();
+            let (sorted, hull) = super::convex_hull_counterclockwise(points, true);
+            let hull = hull.iter().map(|&i| sorted[i]).collect::<Vec<_>>();
+            let m = hull.len();
+            let expected = (0..m)
+                .filter(|&i| hull[i] != hull[(i + 1) % m])
+                .map(|i| {
+                    let (a, b) = (hull[i], hull[(i + 1) % m]);
+                    let height = (0..m)
+                        .map(|k| super::cross_product(&a, &b, &hull[k]).abs())
+                        .max()
+                        .unwrap() as f64;
+                    2.0 * height * height / ((b.0 - a.0).pow(2) + (b.1 - a.1).pow(2)) as f64
+                })
+                .fold(f64::INFINITY, f64::min);
+            let (num, den) = super::integer_polygon_width2(&hull);
+            let expected = if m < 3 { 0.0 } else { expected };
+            assert!(
+                (num as f64 / den as f64 - expected).abs() < 1e-9,
+                "{:?}",
+                hull
+            );
+        }
+    }
+
+    #[test]
+    fn mul_wide() {
+        let max = u128::MAX;
+        assert_eq!(super::mul_wide(max, max), (max - 1, 1));
+        assert_eq!(super::mul_wide(1 << 64, 1 << 64), (1, 0));
+        assert_eq!(super::mul_wide(3, 5), (0, 15));
+        assert!(super::mul_wide(1 << 100, 1 << 30) > super::mul_wide(max, 1));
+    }
+
+    #[test]
+    fn min_perimeter_rectangle() {
+        let perimeter = |r: &[(f64, f64); 4]| {
+            (0..4)
+                .map(|i| {
+                    let (p, q) = (r[i], r[(i + 1) % 4]);
+                    (q.0 - p.0).hypot(q.1 - p.1)
+                })
+                .sum::<f64>()
+        };
+
+        // Aligning with the hypotenuse gives a rectangle of the same area but
+        // a larger perimeter
+        let triangle = [(0.0, 0.0), (4.0, 0.0), (0.0, 3.0)];
+        let rectangle = super::min_perimeter_rectangle(&triangle);
+        assert!((perimeter(&rectangle) - 14.0).abs() < 1e-9);
+
+        // A 4 x 1 rectangle rotated by 30 degrees
+        let (c, s) = (3f64.sqrt() / 2.0, 0.5);
+        let rotate = |(x, y): (f64, f64)| (x * c - y * s, x * s + y * c);
+        let hull = [(0.0, 0.0), (4.0, 0.0), (4.0, 1.0), (0.0, 1.0)].map(rotate);
+        let rectangle = super::min_perimeter_rectangle(&hull);
+        for (p, q) in rectangle.iter().zip(&hull) {
+            assert!((p.0 - q.0).abs() < 1e-9 && (p.1 - q.1).abs() < 1e-9);
+        }
+
+        let segment = [(0.0, 0.0), (3.0, 4.0)];
+        let rectangle = super::min_perimeter_rectangle(&segment);
+        assert!((perimeter(&rectangle) - 10.0).abs() < 1e-9);
+        assert_eq!(
+            super::min_perimeter_rectangle(&[(1.0, 2.0)]),
+            [(1.0, 2.0); 4]
+        );
+
+        // A repeated vertex
+        let hull = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (2.0, 1.0), (0.0, 1.0)];
+        assert_eq!(
+            super::min_perimeter_rectangle(&hull),
+            [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)]
+        );
+        assert_eq!(
+            super::min_perimeter_rectangle(&[(1.0, 2.0), (1.0, 2.0)]),
+            [(1.0, 2.0); 4]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "empty polygon")]
+    fn min_perimeter_rectangle_empty() {
+        super::min_perimeter_rectangle(&[]);
+    }
+
+    #[test]
+    fn min_area_rectangle() {
+        // A 4 x 1 rectangle rotated by 30 degrees, with points inside
+        let (c, s) = (3f64.sqrt() / 2.0, 0.5);
+        let rotate = |(x, y): (f64, f64)| (x * c - y * s, x * s + y * c);
+        let corners = [(0.0, 0.0), (4.0, 0.0), (4.0, 1.0), (0.0, 1.0)].map(rotate);
+        let points = [(1.0, 0.5), (3.0, 0.2), (2.0, 0.9), (4.0, 1.0), (0.0, 0.0)]
+            .map(rotate)
+            .into_iter()
+            .chain(corners)
+            .collect::<Vec<_>>();
+        let (rectangle, area) = super::min_area_rectangle(&points);
+        assert!((area - 4.0).abs() < 1e-9);
+        for p in &rectangle {
+            assert!(corners
+                .iter()
+                .any(|q| (p.0 - q.0).abs() < 1e-9 && (p.1 - q.1).abs() < 1e-9));
+        }
+
+        // The axis-aligned box of a rotated rectangle is larger
+        let (min_x, max_x) = points
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                (lo.min(p.0), hi.max(p.0))
+            });
+        let (min_y, max_y) = points
+            .iter()
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), p| {
+                (lo.min(p.1), hi.max(p.1))
+            });
+        assert!((max_x - min_x) * (max_y - min_y) > area + 1.0);
+
+        // Degenerate inputs
+        let (_, area) = super::min_area_rectangle(&[(0.0, 0.0), (1.0, 1.0), (3.0, 3.0)]);
+        assert_eq!(area, 0.0);
+        assert_eq!(
+            super::min_area_rectangle(&[(1.0, 2.0), (1.0, 2.0)]),
+            ([(1.0, 2.0); 4], 0.0)
+        );
+    }
+
+    #[test]
+    fn caliper_profile() {
+        let hull = [(0.0, 0.0), (4.0, 0.0), (4.0, 1.0), (1.0, 3.0)];
+        let profile = super::caliper_profile(&hull);
+        assert_eq!(profile.len(), hull.len());
+        for (i, &(width, height)) in profile.iter().enumerate() {
+            let (p, q) = (hull[i], hull[(i + 1) % hull.len()]);
+            let length = (q.0 - p.0).hypot(q.1 - p.1);
+            assert!(width >= length - 1e-9);
+            assert!(height > 0.0);
+        }
+        assert_eq!(profile[0], (4.0, 3.0));
+
+        // The best entry matches the minimum perimeter rectangle
+        let best = profile
+            .iter()
+            .map(|&(width, height)| 2.0 * (width + height))
+            .fold(f64::INFINITY, f64::min);
+        let rectangle = super::min_perimeter_rectangle(&hull);
+        let side = |a: (f64, f64), b: (f64, f64)| (b.0 - a.0).hypot(b.1 - a.1);
+        let perimeter = 2.0 * (side(rectangle[0], rectangle[1]) + side(rectangle[1], rectangle[2]));
+        assert!((best - perimeter).abs() < 1e-9);
+
+        // A repeated vertex, including one closing the loop
+        let rectangle = vec![(2.0, 1.0), (1.0, 2.0), (2.0, 1.0), (1.0, 2.0)];
+        let hull = [(0.0, 0.0), (2.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)];
+        assert_eq!(super::caliper_profile(&hull), rectangle);
+        let hull = [(0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0), (0.0, 0.0)];
+        assert_eq!(super::caliper_profile(&hull), rectangle);
+        let hull = [(0.0, 0.0), (0.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)];
+        assert_eq!(super::caliper_profile(&hull), rectangle);
+
+        // A collinear vertex gives its own entry
+        let hull = [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (2.0, 1.0), (0.0, 1.0)];
+        assert_eq!(
+            super::caliper_profile(&hull),
+            vec![(2.0, 1.0), (2.0, 1.0), (1.0, 2.0), (2.0, 1.0), (1.0, 2.0)]
+        );
+
+        assert!(super::caliper_profile(&[(1.0, 1.0)]).is_empty());
+        assert!(super::caliper_profile(&[(1.0, 1.0), (1.0, 1.0)]).is_empty());
+        assert_eq!(
+            super::caliper_profile(&[(0.0, 0.0), (3.0, 4.0)]),
+            vec![(5.0, 0.0), (5.0, 0.0)]
+        );
+    }
 }
@@ -1,4 +1,13 @@
-use std::cmp::Ordering;
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+/// The length of the longer coordinate of the directions made by
+/// `Direction::from_unit`.
+pub const UNIT_RESOLUTION: isize = 1 << 30;
 
 #[derive(Debug, Clone, Copy)]
 pub struct Direction {
@@ -10,6 +19,69 @@ impl Direction {
     pub fn new(x: isize, y: isize) -> Self {
         Self { x, y }
     }
+
+    pub fn x(&self) -> isize {
+        self.x
+    }
+
+    pub fn y(&self) -> isize {
+        self.y
+    }
+
+    /// Checks if the two directions are parallel or antiparallel. The zero
+    /// vector is on the same line as any direction.
+    pub fn same_line(&self, other: &Direction) -> bool {
+        self.x * other.y == self.y * other.x
+    }
+
+    /// Returns the direction as a floating-point vector of length 1, or
+    /// `(0.0, 0.0)` for the zero vector.
+    pub fn to_unit(&self) -> (f64, f64) {
+        let (x, y) = (self.x as f64, self.y as f64);
+        let norm = x.hypot(y);
+        if norm == 0.0 {
+            (0.0, 0.0)
+        } else {
+            (x / norm, y / norm)
+        }
+    }
+
+    /// Snaps a floating-point vector to a lattice direction, scaling it so
+    /// that its longer coordinate is `UNIT_RESOLUTION` and rounding both
+    /// coordinates. The angle is kept within about `1 / UNIT_RESOLUTION`
+    /// radians, and a zero or non-finite vector gives the zero direction.
+    pub fn from_unit(x: f64, y: f64) -> Self {
+        let scale = x.abs().max(y.abs());
+        if scale == 0.0 || !scale.is_finite() {
+            return Self::new(0, 0);
+        }
+        let snap = |c: f64| (c / scale * UNIT_RESOLUTION as f64).round() as isize;
+        Self::new(snap(x), snap(y))
+    }
+
+    /// Returns the quadrant of the direction, numbered counterclockwise from 0
+    /// to 3 so that a smaller quadrant means a smaller direction.
+    ///
+    /// Each quadrant includes the axis at its start: 0 is `x > 0, y >= 0`, 1 is
+    /// `x <= 0, y > 0`, 2 is `x < 0, y <= 0`, and 3 is `x >= 0, y < 0`. The zero
+    /// vector, which is less than any other direction, is in quadrant 0.
+    pub fn quadrant(&self) -> u8 {
+        if self.x > 0 && self.y >= 0 || self.x == 0 && self.y == 0 {
+            0
+        } else if self.x <= 0 && self.y > 0 {
+            1
+        } else if self.x < 0 && self.y <= 0 {
+            2
+        } else {
+            3
+        }
+    }
+}
+
+impl fmt::Display for Direction {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
 }
 
 impl PartialEq for Direction {
@@ -20,6 +92,19 @@ impl PartialEq for Direction {
 
 impl Eq for Direction {}
 
+impl Hash for Direction {
+    /// Hashes the direction in the reduced form with coprime coordinates, so
+    /// that directions equal under `Eq` have the same hash.
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let (mut a, mut b) = (self.x.unsigned_abs(), self.y.unsigned_abs());
+        while b != 0 {
+            (a, b) = (b, a % b);
+        }
+        let g = a.max(1) as isize;
+        (self.x / g, self.y / g).hash(state);
+    }
+}
+
 impl PartialOrd for Direction {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
@@ -86,6 +171,68 @@ impl Ord for Direction {
     }
 }
 
+/// Counts the points whose direction from `pivot` lies in the counterclockwise
+/// arc from `lo` to `hi`, both inclusive. The arc goes past the positive x-axis
+/// if `lo` is greater than `hi`. Points equal to `pivot` are not counted.
+///
+/// # Examples
+///
+/// ```
+/// # use direction::{count_in_arc, Direction};
+/// let points = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+/// let (lo, hi) = (Direction::new(0, -1), Direction::new(0, 1));
+/// assert_eq!(count_in_arc(&points, (0, 0), lo, hi), 3);
+/// assert_eq!(count_in_arc(&points, (0, 0), hi, lo), 3);
+/// ```
+pub fn count_in_arc(
+    points: &[(isize, isize)],
+    pivot: (isize, isize),
+    lo: Direction,
+    hi: Direction,
+) -> usize {
+    points
+        .iter()
+        .filter(|&&p| p != pivot)
+        .map(|&(x, y)| Direction::new(x - pivot.0, y - pivot.1))
+        .filter(|d| {
+            if lo <= hi {
+                lo <= *d && *d <= hi
+            } else {
+                lo <= *d || *d <= hi
+            }
+        })
+        .count()
+}
+
+/// Groups the indices of the points by their direction from `pivot`, so that
+/// each group holds the points on one ray from `pivot`. Points equal to
+/// `pivot` are left out.
+///
+/// # Examples
+///
+/// ```
+/// # use direction::{group_by_direction, Direction};
+/// let points = [(1, 1), (2, 2), (-1, -1), (1, 0)];
+/// let groups = group_by_direction(&points, (0, 0));
+/// assert_eq!(groups.len(), 3);
+/// assert_eq!(groups[&Direction::new(1, 1)], vec![0, 1]);
+/// ```
+pub fn group_by_direction(
+    points: &[(isize, isize)],
+    pivot: (isize, isize),
+) -> HashMap<Direction, Vec<usize>> {
+    let mut groups: HashMap<Direction, Vec<usize>> = HashMap::new();
+    for (i, &(x, y)) in points.iter().enumerate() {
+        if (x, y) != pivot {
+            groups
+                .entry(Direction::new(x - pivot.0, y - pivot.1))
+                .or_default()
+                .push(i);
+        }
+    }
+    groups
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -121,4 +268,119 @@ mod tests {
         let b = Direction::new(1, -1);
         assert_eq!(a.partial_cmp(&b), Some(Ordering::Less));
     }
+
+    #[test]
+    fn display() {
+        let d = Direction::new(-3, 2);
+        assert_eq!((d.x(), d.y()), (-3, 2));
+        assert_eq!(d.to_string(), "(-3, 2)");
+    }
+
+    #[test]
+    fn same_line() {
+        let a = Direction::new(2, 1);
+        assert!(a.same_line(&Direction::new(4, 2)));
+        assert!(a.same_line(&Direction::new(-2, -1)));
+        assert!(!a.same_line(&Direction::new(1, 2)));
+        assert!(!a.same_line(&Direction::new(-2, 1)));
+        assert!(Direction::new(1, 0).same_line(&Direction::new(-1, 0)));
+        assert!(Direction::new(0, 0).same_line(&a));
+    }
+
+    #[test]
+    fn unit() {
+        let mut state = 88172645463325252u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 2001) as isize - 1000
+        };
+        for _ in 0..1000 {
+            let d = Direction::new(next(), next());
+            if d.x() == 0 && d.y() == 0 {
+                continue;
+            }
+            let (x, y) = d.to_unit();
+            assert!((x.hypot(y) - 1.0).abs() < 1e-12);
+            let angle = (d.y() as f64).atan2(d.x() as f64);
+            assert!((y.atan2(x) - angle).abs() < 1e-12);
+
+            let snapped = Direction::from_unit(x, y);
+            let (sx, sy) = (snapped.x() as f64, snapped.y() as f64);
+            assert!((sy.atan2(sx) - angle).abs() < 1e-8);
+            assert_eq!(snapped.quadrant(), d.quadrant());
+        }
+
+        assert_eq!(Direction::from_unit(0.6, -0.8), Direction::new(3, -4));
+        assert_eq!(Direction::from_unit(-1.0, 0.0), Direction::new(-1, 0));
+        assert_eq!(Direction::new(0, 0).to_unit(), (0.0, 0.0));
+        let zero = Direction::from_unit(0.0, 0.0);
+        assert_eq!((zero.x(), zero.y()), (0, 0));
+    }
+
+    #[test]
+    fn quadrant() {
+        assert_eq!(Direction::new(0, 0).quadrant(), 0);
+        assert_eq!(Direction::new(1, 0).quadrant(), 0);
+        assert_eq!(Direction::new(0, 1).quadrant(), 1);
+        assert_eq!(Direction::new(-1, 0).quadrant(), 2);
+        assert_eq!(Direction::new(0, -1).quadrant(), 3);
+        assert_eq!(Direction::new(3, 2).quadrant(), 0);
+        assert_eq!(Direction::new(-3, 2).quadrant(), 1);
+        assert_eq!(Direction::new(-3, -2).quadrant(), 2);
+        assert_eq!(Direction::new(3, -2).quadrant(), 3);
+
+        let mut directions = Vec::new();
+        for x in -2..=2 {
+            for y in -2..=2 {
+                directions.push(Direction::new(x, y));
+            }
+        }
+        for a in &directions {
+            for b in &directions {
+                if a.quadrant() < b.quadrant() {
+                    assert!(a < b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn group_by_direction() {
+        let pivot = (1, 2);
+        let points = [
+            (3, 3),  // (2, 1)
+            (1, 2),  // the pivot itself
+            (5, 4),  // (4, 2)
+            (-1, 1), // (-2, -1)
+            (1, 5),  // (0, 3)
+            (1, 3),  // (0, 1)
+            (7, 5),  // (6, 3)
+        ];
+        let groups = super::group_by_direction(&points, pivot);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(groups[&Direction::new(2, 1)], vec![0, 2, 6]);
+        assert_eq!(groups[&Direction::new(-4, -2)], vec![3]);
+        assert_eq!(groups[&Direction::new(0, 7)], vec![4, 5]);
+    }
+
+    #[test]
+    fn count_in_arc() {
+        let pivot = (1, 1);
+        let points = [
+            (3, 1),  // 0 degrees
+            (3, 2),  // about 27 degrees
+            (2, 2),  // 45 degrees
+            (1, 3),  // 90 degrees
+            (-1, 1), // 180 degrees
+            (3, 0),  // about 333 degrees
+            (2, 0),  // 315 degrees
+            (1, 1),  // the pivot itself
+        ];
+        let lo = Direction::new(1, -1);
+        let hi = Direction::new(2, 2);
+        assert_eq!(super::count_in_arc(&points, pivot, lo, hi), 5);
+        assert_eq!(super::count_in_arc(&points, pivot, hi, lo), 4);
+    }
 }
@@ -1,8 +1,11 @@
 use std::{
     cmp::Ordering,
+    collections::{BTreeMap, BTreeSet, BinaryHeap},
     ops::{Add, Mul, Sub},
 };
 
+use acl_dsu::Dsu;
+
 pub trait UpperBounded {
     fn max_value() -> Self;
 }
@@ -29,25 +32,210 @@ pub fn min_distance2<T>(points: Vec<(T, T)>) -> T
 where
     T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + UpperBounded,
 {
-    let mut points = points;
+    min_distance_by(points, distance2).unwrap_or_else(T::max_value)
+}
+
+/// Same as `min_distance2`, but sorts a copy of `points` so that the caller's
+/// order is kept, and returns `None` if there are fewer than two points.
+///
+/// # Panics
+///
+/// Panics if `T::partial_cmp` returns `None`.
+///
+/// # Examples
+///
+/// ```
+/// # use nearest_points::min_distance2_ref;
+/// let points = [(3, 0), (0, 0), (1, 1)];
+/// assert_eq!(min_distance2_ref(&points), Some(2));
+/// assert_eq!(points, [(3, 0), (0, 0), (1, 1)]);
+/// ```
+pub fn min_distance2_ref<T>(points: &[(T, T)]) -> Option<T>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + UpperBounded,
+{
+    min_distance_by(points.to_vec(), distance2)
+}
+
+/// Finds the minimum distance between two points in `points` under the metric
+/// `dist` by divide and conquer, or `None` if there are fewer than two points.
+///
+/// Points are skipped near the dividing line by comparing the distance to
+/// their projections onto it, so `dist(p, q)` must be at least
+/// `dist(p, (q.0, p.1))` and `dist(p, (p.0, q.1))`, as with the squared
+/// Euclidean distance, the L1 distance, or weighted versions of them. The
+/// running time is O(N log N) for such metrics derived from norms.
+///
+/// # Panics
+///
+/// Panics if `T::partial_cmp` returns `None`.
+///
+/// # Examples
+///
+/// ```
+/// # use nearest_points::min_distance_by;
+/// let points = vec![(0, 0), (3, 1), (5, 5), (1, 3)];
+/// let l1 = |p: &(i64, i64), q: &(i64, i64)| (p.0 - q.0).abs() + (p.1 - q.1).abs();
+/// assert_eq!(min_distance_by(points.clone(), l1), Some(4));
+/// let chebyshev = |p: &(i64, i64), q: &(i64, i64)| (p.0 - q.0).abs().max((p.1 - q.1).abs());
+/// assert_eq!(min_distance_by(points, chebyshev), Some(2));
+/// ```
+pub fn min_distance_by<T, F>(mut points: Vec<(T, T)>, dist: F) -> Option<T>
+where
+    T: Copy + PartialOrd,
+    F: Fn(&(T, T), &(T, T)) -> T,
+{
+    if points.len() < 2 {
+        return None;
+    }
     points.sort_by(|p, q| match p.0.partial_cmp(&q.0).unwrap() {
         Ordering::Equal => p.1.partial_cmp(&q.1).unwrap(),
         ord => ord,
     });
-    min_distance2_inner(&mut points)
+    Some(min_distance_inner(&mut points, &dist))
 }
 
-fn min_distance2_inner<T>(points: &mut [(T, T)]) -> T
+fn distance2<T>(p: &(T, T), q: &(T, T)) -> T
 where
-    T: Copy + PartialOrd + Add<Output = T> + Sub<Output = T> + Mul<Output = T> + UpperBounded,
+    T: Copy + Add<Output = T> + Sub<Output = T> + Mul<Output = T>,
+{
+    (p.0 - q.0) * (p.0 - q.0) + (p.1 - q.1) * (p.1 - q.1)
+}
+
+/// Finds the minimum distance between two points in `points`, or `None` if
+/// there are fewer than two points.
+///
+/// # Panics
+///
+/// Panics if a coordinate is NaN.
+pub fn min_distance(points: Vec<(f64, f64)>) -> Option<f64> {
+    if points.len() < 2 {
+        return None;
+    }
+    Some(min_distance2(points).sqrt())
+}
+
+/// Finds the closest pair of points with a sweep line over the points sorted
+/// by x, keeping the points within the current minimum distance to the left
+/// in a set ordered by y. Returns the indices of the pair, smaller first, and
+/// their squared distance. Runs in O(N log N).
+///
+/// # Panics
+///
+/// Panics if there are fewer than two points.
+///
+/// # Examples
+///
+/// ```
+/// # use nearest_points::closest_pair_sweep;
+/// let points = [(0, 0), (5, 5), (1, 2), (6, 5)];
+/// assert_eq!(closest_pair_sweep(&points), (1, 3, 1));
+/// ```
+pub fn closest_pair_sweep(points: &[(i64, i64)]) -> (usize, usize, i64) {
+    assert!(points.len() >= 2, "fewer than two points");
+    let mut order = (0..points.len()).collect::<Vec<_>>();
+    order.sort_unstable_by_key(|&i| points[i]);
+
+    let distance2 = |i: usize, j: usize| {
+        let (dx, dy) = (points[i].0 - points[j].0, points[i].1 - points[j].1);
+        dx * dx + dy * dy
+    };
+    let (i, j) = (order[0].min(order[1]), order[0].max(order[1]));
+    let mut best = (i, j, distance2(i, j));
+    let mut window = BTreeSet::new();
+    let mut left = 0;
+    for &i in &order {
+        let (x, y) = points[i];
+        while {
+            let dx = x - points[order[left]].0;
+            dx * dx > best.2
+        } {
+            let j = order[left];
+            window.remove(&(points[j].1, j));
+            left += 1;
+        }
+
+        let d = (best.2 as f64).sqrt() as i64 + 1;
+        for &(_, j) in window.range((y - d, 0)..=(y + d, usize::MAX)) {
+            let dist = distance2(i, j);
+            if dist < best.2 {
+                best = (j.min(i), j.max(i), dist);
+            }
+        }
+        window.insert((y, i));
+    }
+    best
+}
+
+/// Computes a minimum spanning tree of the points under the Manhattan
+/// distance and returns its edges as `(u, v, weight)`, in non-decreasing order
+/// of weight. Only O(N) candidate edges are considered, connecting each point
+/// to its nearest neighbor in each of the eight octants, which are found with
+/// a sweep over four transformations of the points. Runs in O(N log N).
+///
+/// # Examples
+///
+/// ```
+/// # use nearest_points::manhattan_mst;
+/// let points = [(0, 0), (2, 1), (5, 1), (1, 3)];
+/// let mst = manhattan_mst(&points);
+/// assert_eq!(mst.iter().map(|e| e.2).sum::<i64>(), 9);
+/// ```
+pub fn manhattan_mst(points: &[(i64, i64)]) -> Vec<(usize, usize, i64)> {
+    let n = points.len();
+    let mut ps = points.to_vec();
+    let mut order = (0..n).collect::<Vec<_>>();
+    let mut edges = Vec::new();
+    for k in 0..4 {
+        order.sort_unstable_by_key(|&i| ps[i].0 + ps[i].1);
+        // Maps -y to the latest point with no neighbor found yet in its octant
+        let mut sweep = BTreeMap::<i64, usize>::new();
+        for &i in &order {
+            while let Some((&key, &j)) = sweep.range(-ps[i].1..).next() {
+                let (dx, dy) = (ps[i].0 - ps[j].0, ps[i].1 - ps[j].1);
+                if dy > dx {
+                    break;
+                }
+                edges.push((dx + dy, i, j));
+                sweep.remove(&key);
+            }
+            sweep.insert(-ps[i].1, i);
+        }
+        for p in &mut ps {
+            if k % 2 == 1 {
+                p.0 = -p.0;
+            } else {
+                *p = (p.1, p.0);
+            }
+        }
+    }
+
+    edges.sort_unstable();
+    let mut dsu = Dsu::new(n);
+    let mut mst = Vec::with_capacity(n.saturating_sub(1));
+    for (w, u, v) in edges {
+        if !dsu.same(u, v) {
+            dsu.merge(u, v);
+            mst.push((u, v, w));
+        }
+    }
+    mst
+}
+
+/// Finds the minimum distance among at least two points sorted by x, and
+/// sorts them by y.
+fn min_distance_inner<T, F>(points: &mut [(T, T)], dist: &F) -> T
+where
+    T: Copy + PartialOrd,
+    F: Fn(&(T, T), &(T, T)) -> T,
 {
     if points.len() <= 3 {
-        let mut dist_min = T::max_value();
-        for (i, &p) in points.iter().enumerate() {
-            for &q in points.iter().skip(i + 1) {
-                let dist = (p.0 - q.0) * (p.0 - q.0) + (p.1 - q.1) * (p.1 - q.1);
-                if dist < dist_min {
-                    dist_min = dist;
+        let mut dist_min = dist(&points[0], &points[1]);
+        for (i, p) in points.iter().enumerate() {
+            for q in points.iter().skip(i + 1) {
+                let d = dist(p, q);
+                if d < dist_min {
+                    dist_min = d;
                 }
             }
         }
@@ -57,8 +245,8 @@ where
 
     let mid = points.len() / 2;
     let x_mid = points[mid].0;
-    let min_left = min_distance2_inner(&mut points[..mid]);
-    let min_right = min_distance2_inner(&mut points[mid..]);
+    let min_left = min_distance_inner(&mut points[..mid], dist);
+    let min_right = min_distance_inner(&mut points[mid..], dist);
     let mut dist_min = if min_left < min_right {
         min_left
     } else {
@@ -69,16 +257,16 @@ where
 
     tmp.clear();
     for p in points {
-        if (p.0 - x_mid) * (p.0 - x_mid) >= dist_min {
+        if dist(p, &(x_mid, p.1)) >= dist_min {
             continue;
         }
         for q in tmp.iter().rev() {
-            if (p.1 - q.1) * (p.1 - q.1) >= dist_min {
+            if dist(p, &(p.0, q.1)) >= dist_min {
                 break;
             }
-            let dist = (p.0 - q.0) * (p.0 - q.0) + (p.1 - q.1) * (p.1 - q.1);
-            if dist < dist_min {
-                dist_min = dist;
+            let d = dist(p, q);
+            if d < dist_min {
+                dist_min = d;
             }
         }
         tmp.push(*p);
@@ -107,3 +295,306 @@ where
     merged.extend(right);
     merged
 }
+
+/// Finds the `k` points closest to `q` and returns their indices along with
+/// the distances to them, sorted by distance. Returns all the points if `k` is
+/// at least the number of points.
+///
+/// # Panics
+///
+/// Panics if a distance is NaN.
+pub fn k_nearest(points: &[(f64, f64)], q: (f64, f64), k: usize) -> Vec<(usize, f64)> {
+    let mut heap = BinaryHeap::with_capacity(k.min(points.len()) + 1);
+    for (idx, p) in points.iter().enumerate() {
+        let dist = (p.0 - q.0) * (p.0 - q.0) + (p.1 - q.1) * (p.1 - q.1);
+        heap.push(Candidate { dist, idx });
+        if heap.len() > k {
+            heap.pop();
+        }
+    }
+    heap.into_sorted_vec()
+        .into_iter()
+        .map(|c| (c.idx, c.dist.sqrt()))
+        .collect()
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Candidate {
+    dist: f64,
+    idx: usize,
+}
+
+impl PartialEq for Candidate {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate {}
+
+impl PartialOrd for Candidate {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Candidate {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match self.dist.partial_cmp(&other.dist).unwrap() {
+            Ordering::Equal => self.idx.cmp(&other.idx),
+            ord => ord,
+        }
+    }
+}
+
+/// A 2-d tree for answering repeated nearest-neighbor queries over a fixed set
+/// of points.
+pub struct KdTree {
+    points: Vec<(f64, f64)>,
+    order: Vec<usize>,
+}
+
+impl KdTree {
+    /// Builds a 2-d tree over `points` in O(N log N).
+    pub fn build(points: Vec<(f64, f64)>) -> Self {
+        let mut order = (0..points.len()).collect::<Vec<_>>();
+        build_inner(&points, &mut order, true);
+        Self { points, order }
+    }
+
+    /// Finds the stored point closest to `q` and returns its index in the
+    /// vector given to `build` along with the distance to it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tree is empty.
+    pub fn nearest(&self, q: (f64, f64)) -> (usize, f64) {
+        assert!(!self.points.is_empty(), "the tree is empty");
+        let mut best = (usize::MAX, f64::INFINITY);
+        self.nearest_inner(&self.order, true, q, &mut best);
+        (best.0, best.1.sqrt())
+    }
+
+    fn nearest_inner(&self, order: &[usize], by_x: bool, q: (f64, f64), best: &mut (usize, f64)) {
+        if order.is_empty() {
+            return;
+        }
+        let mid = order.len() / 2;
+        let p = self.points[order[mid]];
+        let dist = (p.0 - q.0) * (p.0 - q.0) + (p.1 - q.1) * (p.1 - q.1);
+        if dist < best.1 {
+            *best = (order[mid], dist);
+        }
+
+        let diff = if by_x { q.0 - p.0 } else { q.1 - p.1 };
+        let (near, far) = if diff < 0.0 {
+            (&order[..mid], &order[mid + 1..])
+        } else {
+            (&order[mid + 1..], &order[..mid])
+        };
+        self.nearest_inner(near, !by_x, q, best);
+        if diff * diff < best.1 {
+            self.nearest_inner(far, !by_x, q, best);
+        }
+    }
+}
+
+/// Arranges `order` so that the median by x (or y if `by_x` is false) is in the
+/// middle, with smaller points to its left and larger ones to its right, and
+/// recurses into both halves.
+fn build_inner(points: &[(f64, f64)], order: &mut [usize], by_x: bool) {
+    if order.len() <= 1 {
+        return;
+    }
+    let mid = order.len() / 2;
+    if by_x {
+        order.select_nth_unstable_by(mid, |&i, &j| points[i].0.partial_cmp(&points[j].0).unwrap());
+    } else {
+        order.select_nth_unstable_by(mid, |&i, &j| points[i].1.partial_cmp(&points[j].1).unwrap());
+    }
+    let (left, right) = order.split_at_mut(mid);
+    build_inner(points, left, !by_x);
+    build_inner(points, &mut right[1..], !by_x);
+}
+
+#[cfg(test)]
+mod tests {
+    fn xorshift(state: &mut u64) -> f64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        (*state % 1_000_000) as f64 / 1000.0
+    }
+
+    #[test]
+    fn kd_tree_nearest() {
+        let mut state = 88172645463325252;
+        let points = (0..500)
+            .map(|_| (xorshift(&mut state), xorshift(&mut state)))
+            .collect::<Vec<_>>();
+        let tree = super::KdTree::build(points.clone());
+        for _ in 0..500 {
+            let q = (xorshift(&mut state), xorshift(&mut state));
+            let (idx, dist) = tree.nearest(q);
+            let expected = points
+                .iter()
+                .map(|p| ((p.0 - q.0) * (p.0 - q.0) + (p.1 - q.1) * (p.1 - q.1)).sqrt())
+                .fold(f64::INFINITY, f64::min);
+            assert_eq!(dist, expected);
+            let p = points[idx];
+            assert_eq!(
+                ((p.0 - q.0) * (p.0 - q.0) + (p.1 - q.1) * (p.1 - q.1)).sqrt(),
+                dist
+            );
+        }
+    }
+
+    #[test]
+    fn k_nearest() {
+        let points = vec![(0.0, 0.0), (3.0, 0.0), (1.0, 1.0), (0.0, 2.0), (5.0, 5.0)];
+        let nearest = super::k_nearest(&points, (0.5, 0.0), 3);
+        assert_eq!(
+            nearest.iter().map(|&(idx, _)| idx).collect::<Vec<_>>(),
+            vec![0, 2, 3]
+        );
+        assert_eq!(nearest[0].1, 0.5);
+        assert!(nearest.windows(2).all(|w| w[0].1 <= w[1].1));
+
+        let all = super::k_nearest(&points, (0.5, 0.0), 10);
+        assert_eq!(
+            all.iter().map(|&(idx, _)| idx).collect::<Vec<_>>(),
+            vec![0, 2, 3, 1, 4]
+        );
+        assert_eq!(super::k_nearest(&points, (0.5, 0.0), usize::MAX), all);
+
+        assert!(super::k_nearest(&points, (0.5, 0.0), 0).is_empty());
+    }
+
+    #[test]
+    fn manhattan_mst() {
+        use acl_dsu::Dsu;
+
+        let mut state = 2463534242;
+        for n in [0, 1, 2, 5, 20, 60] {
+            let points = (0..n)
+                .map(|_| {
+                    let x = (xorshift(&mut state) / 50.0) as i64 - 10;
+                    let y = (xorshift(&mut state) / 50.0) as i64 - 10;
+                    (x, y)
+                })
+                .collect::<Vec<_>>();
+
+            let mut all_edges = Vec::new();
+            for i in 0..n {
+                for j in i + 1..n {
+                    let (p, q) = (points[i], points[j]);
+                    all_edges.push(((p.0 - q.0).abs() + (p.1 - q.1).abs(), i, j));
+                }
+            }
+            all_edges.sort();
+            let mut dsu = Dsu::new(n);
+            let mut expected = 0;
+            for (w, i, j) in all_edges {
+                if !dsu.same(i, j) {
+                    dsu.merge(i, j);
+                    expected += w;
+                }
+            }
+
+            let mst = super::manhattan_mst(&points);
+            assert_eq!(mst.len(), n.saturating_sub(1));
+            assert_eq!(mst.iter().map(|e| e.2).sum::<i64>(), expected);
+            let mut dsu = Dsu::new(n);
+            for &(u, v, w) in &mst {
+                let (p, q) = (points[u], points[v]);
+                assert_eq!((p.0 - q.0).abs() + (p.1 - q.1).abs(), w);
+                dsu.merge(u, v);
+            }
+            assert!((0..n).all(|v| dsu.same(0, v)));
+        }
+    }
+
+    #[test]
+    fn closest_pair_sweep() {
+        let mut state = 88172645463325252;
+        for n in [2, 3, 10, 100, 1000] {
+            let points = (0..n)
+                .map(|_| {
+                    let x = (xorshift(&mut state) * 2.0) as i64 - 1000;
+                    let y = (xorshift(&mut state) * 2.0) as i64 - 1000;
+                    (x, y)
+                })
+                .collect::<Vec<_>>();
+            let (i, j, dist) = super::closest_pair_sweep(&points);
+            assert!(i < j);
+            let (dx, dy) = (points[i].0 - points[j].0, points[i].1 - points[j].1);
+            assert_eq!(dx * dx + dy * dy, dist);
+            assert_eq!(super::min_distance2(points), dist);
+        }
+
+        let points = [(3, 3), (0, 0), (7, 1), (3, 3)];
+        assert_eq!(super::closest_pair_sweep(&points), (0, 3, 0));
+    }
+
+    #[test]
+    fn min_distance2_ref() {
+        let points = vec![(5, 5), (-3, 2), (0, 0), (4, 7), (-1, 1), (9, -2)];
+        let original = points.clone();
+        assert_eq!(super::min_distance2_ref(&points), Some(2));
+        assert_eq!(points, original);
+        assert_eq!(super::min_distance2(points), 2);
+
+        assert_eq!(super::min_distance2_ref::<i64>(&[]), None);
+        assert_eq!(super::min_distance2_ref(&[(1, 1)]), None);
+        assert_eq!(super::min_distance2(vec![(1, 1)]), i64::MAX);
+    }
+
+    #[test]
+    fn min_distance_by() {
+        let mut state = 88172645463325252;
+        let squared = |p: &(i64, i64), q: &(i64, i64)| {
+            let (dx, dy) = (p.0 - q.0, p.1 - q.1);
+            dx * dx + dy * dy
+        };
+        // Weighs the x difference three times as much as the y difference
+        let weighted_l1 =
+            |p: &(i64, i64), q: &(i64, i64)| 3 * (p.0 - q.0).abs() + (p.1 - q.1).abs();
+        for n in [2, 3, 10, 100, 1000] {
+            let points = (0..n)
+                .map(|_| {
+                    let x = (xorshift(&mut state) * 2.0) as i64 - 1000;
+                    let y = (xorshift(&mut state) * 2.0) as i64 - 1000;
+                    (x, y)
+                })
+                .collect::<Vec<_>>();
+            let pairs = || (0..n).flat_map(|i| (i + 1..n).map(move |j| (i, j)));
+            let expected = pairs().map(|(i, j)| squared(&points[i], &points[j])).min();
+            assert_eq!(super::min_distance_by(points.clone(), squared), expected);
+
+            let expected = pairs()
+                .map(|(i, j)| weighted_l1(&points[i], &points[j]))
+                .min();
+            assert_eq!(super::min_distance_by(points, weighted_l1), expected);
+        }
+        assert_eq!(super::min_distance_by(vec![(1, 1)], squared), None);
+    }
+
+    #[test]
+    fn min_distance() {
+        let mut state = 2463534242;
+        let points = (0..300)
+            .map(|_| (xorshift(&mut state), xorshift(&mut state)))
+            .collect::<Vec<_>>();
+        let mut expected = f64::INFINITY;
+        for (i, p) in points.iter().enumerate() {
+            for q in &points[i + 1..] {
+                expected =
+                    expected.min(((p.0 - q.0) * (p.0 - q.0) + (p.1 - q.1) * (p.1 - q.1)).sqrt());
+            }
+        }
+        assert_eq!(super::min_distance(points), Some(expected));
+
+        assert_eq!(super::min_distance(vec![(1.0, 1.0)]), None);
+        assert_eq!(super::min_distance(vec![(0.0, 0.0), (3.0, 4.0)]), Some(5.0));
+    }
+}
@@ -0,0 +1,196 @@
+type Point = (f64, f64);
+
+/// A circle given by its center and radius.
+pub type Circle = (Point, f64);
+
+const EPS: f64 = 1e-9;
+
+/// Finds the smallest circle enclosing all the points with Welzl's randomized
+/// incremental algorithm in expected O(N) time, or `None` if there are no
+/// points.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::circle::min_enclosing_circle;
+/// let points = [(0.0, 0.0), (2.0, 0.0), (1.0, 0.5)];
+/// assert_eq!(min_enclosing_circle(&points), Some(((1.0, 0.0), 1.0)));
+/// ```
+pub fn min_enclosing_circle(points: &[Point]) -> Option<Circle> {
+    // Shuffle the points, as the expected running time relies on a random order
+    let mut points = points.to_vec();
+    let mut state = 0x2545_f491_4f6c_dd1d_u64;
+    for i in (1..points.len()).rev() {
+        state ^= state << 13;
+        state ^= state >> 7;
+        state ^= state << 17;
+        points.swap(i, (state % (i as u64 + 1)) as usize);
+    }
+
+    let mut circle = (*points.first()?, 0.0);
+    for i in 1..points.len() {
+        if !contains(circle, points[i]) {
+            circle = with_one_boundary_point(&points[..i], points[i]);
+        }
+    }
+    Some(circle)
+}
+
+/// Maintains the smallest circle enclosing a stream of points, using Welzl's
+/// algorithm with the move-to-front heuristic. A point outside the current
+/// circle lies on the boundary of the new one, which is found in O(N) time,
+/// and the point is then moved to the front so that later recomputations
+/// meet it early.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::circle::MinEnclosingCircle;
+/// let mut circle = MinEnclosingCircle::new();
+/// assert_eq!(circle.circle(), None);
+/// circle.add_point((0.0, 0.0));
+/// circle.add_point((2.0, 0.0));
+/// assert_eq!(circle.circle(), Some(((1.0, 0.0), 1.0)));
+/// circle.add_point((1.0, 0.5));
+/// assert_eq!(circle.circle(), Some(((1.0, 0.0), 1.0)));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct MinEnclosingCircle {
+    points: Vec<Point>,
+    circle: Option<Circle>,
+}
+
+impl MinEnclosingCircle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the smallest circle enclosing the points added so far, or `None`
+    /// if no point has been added.
+    pub fn circle(&self) -> Option<Circle> {
+        self.circle
+    }
+
+    pub fn add_point(&mut self, p: Point) {
+        match self.circle {
+            Some(circle) if contains(circle, p) => self.points.push(p),
+            _ => {
+                self.circle = Some(with_one_boundary_point(&self.points, p));
+                self.points.insert(0, p);
+            }
+        }
+    }
+}
+
+/// Finds the smallest circle enclosing `points` with `p` on its boundary.
+fn with_one_boundary_point(points: &[Point], p: Point) -> Circle {
+    let mut circle = (p, 0.0);
+    for i in 0..points.len() {
+        if !contains(circle, points[i]) {
+            circle = with_two_boundary_points(&points[..i], p, points[i]);
+        }
+    }
+    circle
+}
+
+/// Finds the smallest circle enclosing `points` with `p` and `q` on its
+/// boundary.
+fn with_two_boundary_points(points: &[Point], p: Point, q: Point) -> Circle {
+    let mut circle = diameter_circle(p, q);
+    for &r in points {
+        if !contains(circle, r) {
+            circle = circumcircle(p, q, r);
+        }
+    }
+    circle
+}
+
+fn contains((center, radius): Circle, p: Point) -> bool {
+    distance(center, p) <= radius + EPS * (1.0 + radius)
+}
+
+fn distance(a: Point, b: Point) -> f64 {
+    (a.0 - b.0).hypot(a.1 - b.1)
+}
+
+fn diameter_circle(p: Point, q: Point) -> Circle {
+    let center = ((p.0 + q.0) / 2.0, (p.1 + q.1) / 2.0);
+    (center, distance(p, q) / 2.0)
+}
+
+fn circumcircle(p: Point, q: Point, r: Point) -> Circle {
+    let (bx, by) = (q.0 - p.0, q.1 - p.1);
+    let (cx, cy) = (r.0 - p.0, r.1 - p.1);
+    let d = 2.0 * (bx * cy - by * cx);
+    if d.abs() < EPS {
+        // Nearly collinear, so the two farthest points span the circle
+        return [
+            diameter_circle(p, q),
+            diameter_circle(q, r),
+            diameter_circle(r, p),
+        ]
+        .into_iter()
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .unwrap();
+    }
+    let b2 = bx * bx + by * by;
+    let c2 = cx * cx + cy * cy;
+    let ux = (cy * b2 - by * c2) / d;
+    let uy = (bx * c2 - cx * b2) / d;
+    ((p.0 + ux, p.1 + uy), ux.hypot(uy))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MinEnclosingCircle;
+
+    fn xorshift(state: &mut u64) -> f64 {
+        *state ^= *state << 13;
+        *state ^= *state >> 7;
+        *state ^= *state << 17;
+        (*state % 1_000_000) as f64 / 1000.0
+    }
+
+    #[test]
+    fn min_enclosing_circle_online() {
+        let mut state = 88172645463325252;
+        for n in [1, 2, 3, 10, 100, 1000] {
+            let points = (0..n)
+                .map(|_| (xorshift(&mut state), xorshift(&mut state)))
+                .collect::<Vec<_>>();
+
+            let mut online = MinEnclosingCircle::new();
+            let mut radius = 0.0;
+            for &p in &points {
+                online.add_point(p);
+                let (_, r) = online.circle().unwrap();
+                assert!(r >= radius - 1e-9);
+                radius = r;
+            }
+
+            let (center, radius) = online.circle().unwrap();
+            let (batch_center, batch_radius) = super::min_enclosing_circle(&points).unwrap();
+            assert!((radius - batch_radius).abs() < 1e-6);
+            assert!(super::distance(center, batch_center) < 1e-6);
+            for &p in &points {
+                assert!(super::distance(center, p) <= radius + 1e-6);
+            }
+        }
+        assert_eq!(super::min_enclosing_circle(&[]), None);
+    }
+
+    #[test]
+    fn min_enclosing_circle_triangle() {
+        // An acute triangle is enclosed by its circumcircle
+        let points = [(0.0, 0.0), (4.0, 0.0), (2.0, 3.0)];
+        let (center, radius) = super::min_enclosing_circle(&points).unwrap();
+        assert!(super::distance(center, (2.0, 5.0 / 6.0)) < 1e-9);
+        assert!((radius - 13.0 / 6.0).abs() < 1e-9);
+
+        // Collinear points are enclosed by the circle through the extremes
+        let points = [(0.0, 0.0), (1.0, 1.0), (3.0, 3.0), (2.0, 2.0)];
+        let (center, radius) = super::min_enclosing_circle(&points).unwrap();
+        assert!(super::distance(center, (1.5, 1.5)) < 1e-9);
+        assert!((radius - 4.5f64.sqrt()).abs() < 1e-9);
+    }
+}
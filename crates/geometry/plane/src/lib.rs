@@ -1,6 +1,10 @@
 use std::cmp::Ordering;
 
+pub use convex_hull::Point2;
+
+pub mod circle;
 pub mod line;
+pub mod polygon;
 
 /// Calculates the area of a polygon using the shoelace formula.
 ///
@@ -13,22 +17,84 @@ pub mod line;
 /// assert_eq!(area, 1.0);
 /// ```
 pub fn shoelace_formula(vertices: &[(f64, f64)]) -> f64 {
-    if vertices.len() < 3 {
-        return 0.0;
-    }
+    shoelace_iter(vertices.iter().copied())
+}
+
+/// Calculates the area of a polygon given as a stream of vertices using the
+/// shoelace formula, without collecting the vertices first.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::shoelace_iter;
+/// let vertices = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+/// let area = shoelace_iter(vertices.iter().map(|&(x, y)| (2.0 * x, 2.0 * y)));
+/// assert_eq!(area, 4.0);
+/// ```
+pub fn shoelace_iter<I: IntoIterator<Item = (f64, f64)>>(vertices: I) -> f64 {
+    let mut vertices = vertices.into_iter();
+    let first = match vertices.next() {
+        Some(first) => first,
+        None => return 0.0,
+    };
 
     let mut sum1 = 0.0;
     let mut sum2 = 0.0;
 
-    for i in 0..vertices.len() {
-        let j = (i + 1) % vertices.len(); // Next index, wraps around to 0 at the end
-        sum1 += vertices[i].0 * vertices[j].1;
-        sum2 += vertices[i].1 * vertices[j].0;
+    let mut prev = first;
+    for v in vertices.chain(Some(first)) {
+        sum1 += prev.0 * v.1;
+        sum2 += prev.1 * v.0;
+        prev = v;
     }
 
     ((sum1 - sum2).abs()) / 2.0
 }
 
+/// Calculates the area of a polygon using the shoelace formula, or returns
+/// `None` if the polygon is not simple and thus has no meaningful area.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::area_checked;
+/// let square = [(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)];
+/// assert_eq!(area_checked(&square), Some(1.0));
+/// let bowtie = [(0.0, 0.0), (1.0, 1.0), (1.0, 0.0), (0.0, 1.0)];
+/// assert_eq!(area_checked(&bowtie), None);
+/// ```
+pub fn area_checked(vertices: &[(f64, f64)]) -> Option<f64> {
+    if polygon::is_simple(vertices) {
+        Some(shoelace_formula(vertices))
+    } else {
+        None
+    }
+}
+
+/// Reverses the order of the vertices if they are in clockwise order, so that
+/// the polygon is always in counterclockwise order afterwards.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::to_ccw;
+/// let mut square = vec![(0.0, 0.0), (0.0, 1.0), (1.0, 1.0), (1.0, 0.0)];
+/// to_ccw(&mut square);
+/// assert_eq!(square, vec![(1.0, 0.0), (1.0, 1.0), (0.0, 1.0), (0.0, 0.0)]);
+/// ```
+pub fn to_ccw(polygon: &mut [(f64, f64)]) {
+    let n = polygon.len();
+    let signed_area2 = (0..n)
+        .map(|i| {
+            let (p, q) = (polygon[i], polygon[(i + 1) % n]);
+            p.0 * q.1 - p.1 * q.0
+        })
+        .sum::<f64>();
+    if signed_area2 < 0.0 {
+        polygon.reverse();
+    }
+}
+
 fn minf64(a: f64, b: f64) -> f64 {
     match a.partial_cmp(&b) {
         Some(Ordering::Less) | Some(Ordering::Equal) => a,
@@ -61,3 +127,43 @@ fn cmpf64(a: f64, b: f64) -> Ordering {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn shoelace_iter() {
+        let vertices = vec![(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (2.0, 5.0), (0.0, 3.0)];
+        assert_eq!(
+            super::shoelace_iter(vertices.iter().copied()),
+            super::shoelace_formula(&vertices)
+        );
+        assert_eq!(super::shoelace_iter(vertices), 16.0);
+        assert_eq!(super::shoelace_iter(Vec::new()), 0.0);
+    }
+
+    #[test]
+    fn area_checked() {
+        let vertices = vec![(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (2.0, 5.0), (0.0, 3.0)];
+        assert_eq!(super::area_checked(&vertices), Some(16.0));
+
+        let bowtie = vec![(0.0, 0.0), (4.0, 4.0), (4.0, 0.0), (0.0, 4.0)];
+        assert_eq!(super::shoelace_formula(&bowtie), 0.0);
+        assert_eq!(super::area_checked(&bowtie), None);
+    }
+
+    #[test]
+    fn to_ccw() {
+        let ccw = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+        let mut polygon = ccw.clone();
+        super::to_ccw(&mut polygon);
+        assert_eq!(polygon, ccw);
+
+        let mut polygon = ccw.iter().rev().copied().collect::<Vec<_>>();
+        super::to_ccw(&mut polygon);
+        assert_eq!(polygon, ccw);
+
+        let mut empty = Vec::new();
+        super::to_ccw(&mut empty);
+        assert!(empty.is_empty());
+    }
+}
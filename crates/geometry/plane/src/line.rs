@@ -1,32 +1,170 @@
 use std::{
+    cell::Cell,
     cmp::Ordering,
-    collections::BTreeSet,
+    collections::{BTreeMap, BTreeSet},
     mem,
     ops::{Mul, Sub},
 };
 
-use crate::{cmpf64, maxf64, minf64};
+use crate::{cmpf64, maxf64, minf64, Point2};
 
 type Point<C> = (C, C);
 
 #[derive(Clone, Copy, Debug, Default, PartialEq)]
 pub struct Segment<C>(pub Point<C>, pub Point<C>);
 
+impl<C: Copy> Segment<C> {
+    /// Creates a segment from `p` to `q`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use plane::line::Segment;
+    /// let segment = Segment::new((0.0, 0.0), (2.0, 4.0));
+    /// assert_eq!(segment.start(), (0.0, 0.0));
+    /// assert_eq!(segment.end(), (2.0, 4.0));
+    /// assert_eq!(segment.midpoint(), (1.0, 2.0));
+    /// ```
+    pub fn new(p: Point<C>, q: Point<C>) -> Self {
+        Self(p, q)
+    }
+
+    pub fn start(&self) -> Point<C> {
+        self.0
+    }
+
+    pub fn end(&self) -> Point<C> {
+        self.1
+    }
+}
+
 impl Eq for Segment<f64> {}
 
 impl Segment<f64> {
-    fn cmp_at_start(&self, other: &Self) -> Ordering {
-        let x = maxf64(minf64(self.0 .0, self.1 .0), minf64(other.0 .0, other.1 .0));
-        cmpf64(self.y(x), other.y(x))
+    pub fn midpoint(&self) -> Point<f64> {
+        ((self.0 .0 + self.1 .0) / 2.0, (self.0 .1 + self.1 .1) / 2.0)
+    }
+
+    /// Returns the Euclidean length of the segment.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use plane::line::Segment;
+    /// let segment = Segment((1.0, 1.0), (4.0, 5.0));
+    /// assert_eq!(segment.length(), 5.0);
+    /// assert_eq!(segment.unit(), (0.6, 0.8));
+    /// ```
+    pub fn length(&self) -> f64 {
+        (self.1 .0 - self.0 .0).hypot(self.1 .1 - self.0 .1)
+    }
+
+    /// Returns the unit vector pointing from the start to the end of the
+    /// segment, or `(0.0, 0.0)` if the segment has zero length.
+    pub fn unit(&self) -> Point<f64> {
+        let length = self.length();
+        if length == 0.0 {
+            (0.0, 0.0)
+        } else {
+            (
+                (self.1 .0 - self.0 .0) / length,
+                (self.1 .1 - self.0 .1) / length,
+            )
+        }
     }
 
     fn y(&self, x: f64) -> f64 {
         if (self.0 .0 - self.1 .0).abs() < f64::EPSILON {
-            self.0 .1
+            minf64(self.0 .1, self.1 .1)
         } else {
             self.0 .1 + (x - self.0 .0) * (self.1 .1 - self.0 .1) / (self.1 .0 - self.0 .0)
         }
     }
+
+    fn slope(&self) -> f64 {
+        if (self.0 .0 - self.1 .0).abs() < f64::EPSILON {
+            f64::INFINITY
+        } else {
+            (self.1 .1 - self.0 .1) / (self.1 .0 - self.0 .0)
+        }
+    }
+}
+
+impl Segment<i64> {
+    /// Returns the y-coordinate at `x` as a fraction with a positive
+    /// denominator.
+    fn y(&self, x: i64) -> (i128, i128) {
+        let (x1, y1) = (i128::from(self.0 .0), i128::from(self.0 .1));
+        let (x2, y2) = (i128::from(self.1 .0), i128::from(self.1 .1));
+        if x1 == x2 {
+            (y1.min(y2), 1)
+        } else {
+            let num = y1 * (x2 - x1) + (i128::from(x) - x1) * (y2 - y1);
+            if x1 < x2 {
+                (num, x2 - x1)
+            } else {
+                (-num, x1 - x2)
+            }
+        }
+    }
+
+    /// Returns the slope as a fraction with a non-negative denominator, which
+    /// is zero for vertical segments.
+    fn slope(&self) -> (i128, i128) {
+        let dx = i128::from(self.1 .0) - i128::from(self.0 .0);
+        let dy = i128::from(self.1 .1) - i128::from(self.0 .1);
+        match dx.cmp(&0) {
+            Ordering::Less => (-dy, -dx),
+            Ordering::Equal => (1, 0),
+            Ordering::Greater => (dy, dx),
+        }
+    }
+}
+
+/// A coordinate type over which `find_intersecting_segments` can sweep.
+pub trait SweepCoord:
+    Copy + Default + PartialOrd + Sub<Output = Self> + Mul<Output = Self>
+{
+    /// Compares the x-coordinates of two sweep events.
+    fn cmp_x(a: Self, b: Self) -> Ordering;
+
+    /// Compares the y-coordinates of two segments at the larger of their
+    /// leftmost x-coordinates, where both of them are in the sweep status.
+    /// Segments meeting there are ordered by slope, with vertical segments
+    /// placed at their lower endpoint and above any other segment.
+    fn cmp_at_start(a: &Segment<Self>, b: &Segment<Self>) -> Ordering;
+}
+
+impl SweepCoord for f64 {
+    fn cmp_x(a: f64, b: f64) -> Ordering {
+        if (a - b).abs() > f64::EPSILON {
+            cmpf64(a, b)
+        } else {
+            Ordering::Equal
+        }
+    }
+
+    fn cmp_at_start(a: &Segment<f64>, b: &Segment<f64>) -> Ordering {
+        let x = maxf64(minf64(a.0 .0, a.1 .0), minf64(b.0 .0, b.1 .0));
+        cmpf64(a.y(x), b.y(x)).then_with(|| cmpf64(a.slope(), b.slope()))
+    }
+}
+
+impl SweepCoord for i64 {
+    fn cmp_x(a: i64, b: i64) -> Ordering {
+        a.cmp(&b)
+    }
+
+    fn cmp_at_start(a: &Segment<i64>, b: &Segment<i64>) -> Ordering {
+        let x = a.0 .0.min(a.1 .0).max(b.0 .0.min(b.1 .0));
+        let (num_a, den_a) = a.y(x);
+        let (num_b, den_b) = b.y(x);
+        (num_a * den_b).cmp(&(num_b * den_a)).then_with(|| {
+            let (dy_a, dx_a) = a.slope();
+            let (dy_b, dx_b) = b.slope();
+            (dy_a * dx_b).cmp(&(dy_b * dx_a))
+        })
+    }
 }
 
 /// Checks if two line segments have any point in common.
@@ -136,11 +274,11 @@ where
 /// Returns a positive value if `o`, `a`, and `b` make a counter-clockwise turn,
 /// a negative value if they make a clockwise turn, and zero if they are
 /// collinear.
-pub fn cross_product<C>(o: (C, C), a: (C, C), b: (C, C)) -> C
+pub fn cross_product<P: Point2>(o: P, a: P, b: P) -> P::Coord
 where
-    C: Copy + Sub<Output = C> + Mul<Output = C>,
+    P::Coord: Sub<Output = P::Coord> + Mul<Output = P::Coord>,
 {
-    (a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)
+    (a.x() - o.x()) * (b.y() - o.y()) - (a.y() - o.y()) * (b.x() - o.x())
 }
 
 /// Checks if a point `r` is on or in the rectangle parallel to the axes
@@ -188,70 +326,122 @@ pub fn is_in_rectangle<C: Copy + PartialOrd>(p: Point<C>, diagonal: Segment<C>)
     )
 }
 
+/// Computes the distance from a point to a line segment.
+pub fn point_segment_distance(p: Point<f64>, seg: Segment<f64>) -> f64 {
+    let Segment(a, b) = seg;
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len2 = dx * dx + dy * dy;
+    let t = if len2 == 0.0 {
+        0.0
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len2).clamp(0.0, 1.0)
+    };
+    let (x, y) = (a.0 + t * dx, a.1 + t * dy);
+    ((p.0 - x) * (p.0 - x) + (p.1 - y) * (p.1 - y)).sqrt()
+}
+
+/// Computes the minimum distance between two line segments, which is zero if
+/// they intersect.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::line::{Segment, segment_to_segment_distance};
+/// let a = Segment((0.0, 0.0), (2.0, 0.0));
+/// let b = Segment((1.0, 1.0), (1.0, 3.0));
+/// assert_eq!(segment_to_segment_distance(a, b), 1.0);
+/// ```
+pub fn segment_to_segment_distance(a: Segment<f64>, b: Segment<f64>) -> f64 {
+    if do_intersect(a, b) {
+        return 0.0;
+    }
+    [
+        point_segment_distance(a.0, b),
+        point_segment_distance(a.1, b),
+        point_segment_distance(b.0, a),
+        point_segment_distance(b.1, a),
+    ]
+    .into_iter()
+    .fold(f64::INFINITY, f64::min)
+}
+
 #[derive(Clone, Copy, Debug)]
-struct Event {
-    x: f64,
+struct Event<C> {
+    x: C,
     is_start: bool,
     id: usize,
 }
 
-impl PartialOrd for Event {
+impl<C: SweepCoord> PartialOrd for Event<C> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for Event {
+impl<C: SweepCoord> Ord for Event<C> {
     fn cmp(&self, other: &Self) -> Ordering {
-        if (self.x - other.x).abs() > f64::EPSILON {
-            cmpf64(self.x, other.x)
-        } else {
-            other.is_start.cmp(&self.is_start)
+        match C::cmp_x(self.x, other.x) {
+            Ordering::Equal => other.is_start.cmp(&self.is_start),
+            ord => ord,
         }
     }
 }
 
-impl PartialEq for Event {
+impl<C: SweepCoord> PartialEq for Event<C> {
     fn eq(&self, other: &Self) -> bool {
-        self.x == other.x && self.is_start == other.is_start
+        self.cmp(other) == Ordering::Equal
     }
 }
 
-impl Eq for Event {}
+impl<C: SweepCoord> Eq for Event<C> {}
 
-#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
-struct ActiveSegment {
-    segment: Segment<f64>,
+#[derive(Clone, Copy, Debug)]
+struct ActiveSegment<C> {
+    segment: Segment<C>,
     id: usize,
 }
 
-impl PartialOrd for ActiveSegment {
+impl<C: SweepCoord> PartialOrd for ActiveSegment<C> {
     fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
         Some(self.cmp(other))
     }
 }
 
-impl Ord for ActiveSegment {
+impl<C: SweepCoord> Ord for ActiveSegment<C> {
     fn cmp(&self, other: &Self) -> Ordering {
-        match self.segment.cmp_at_start(&other.segment) {
+        match C::cmp_at_start(&self.segment, &other.segment) {
             Ordering::Equal => self.id.cmp(&other.id),
             other => other,
         }
     }
 }
 
+impl<C: SweepCoord> PartialEq for ActiveSegment<C> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<C: SweepCoord> Eq for ActiveSegment<C> {}
+
 /// Finds the first pair of segments that have any point in common.
-pub fn find_intersecting_segments(segments: &[Segment<f64>]) -> Option<(usize, usize)> {
+pub fn find_intersecting_segments<C: SweepCoord>(
+    segments: &[Segment<C>],
+) -> Option<(usize, usize)> {
     find_intersecting_segments_by(segments, do_intersect)
 }
 
 /// Finds the first pair of segments that share a point according to the given
 /// predicate.
-pub fn find_intersecting_segments_by(
-    segments: &[Segment<f64>],
-    do_intersect: fn(Segment<f64>, Segment<f64>) -> bool,
+pub fn find_intersecting_segments_by<C: SweepCoord>(
+    segments: &[Segment<C>],
+    do_intersect: fn(Segment<C>, Segment<C>) -> bool,
 ) -> Option<(usize, usize)> {
-    let mut events: Vec<Event> = Vec::new();
+    if segments.len() < 2 {
+        return None;
+    }
+
+    let mut events: Vec<Event<C>> = Vec::new();
     for (i, &segment) in segments.iter().enumerate() {
         let Segment(mut p, mut q) = segment;
         if p.0 > q.0 {
@@ -270,7 +460,7 @@ pub fn find_intersecting_segments_by(
     }
     events.sort_unstable();
 
-    let mut active_segments = BTreeSet::<ActiveSegment>::new();
+    let mut active_segments = BTreeSet::<ActiveSegment<C>>::new();
     for event in events {
         let segment = ActiveSegment {
             segment: segments[event.id],
@@ -290,7 +480,11 @@ pub fn find_intersecting_segments_by(
             active_segments.insert(segment);
         } else {
             let mut iter = active_segments.range(segment..);
-            iter.next().unwrap();
+            if iter.next().map(|s| s.id) != Some(event.id) {
+                // The segment could not be found again if the comparisons were
+                // inconsistent, e.g. due to rounding
+                continue;
+            }
             if let (Some(next), Some(prev)) =
                 (iter.next(), active_segments.range(..segment).next_back())
             {
@@ -327,6 +521,299 @@ pub fn intersection(a: &Segment<f64>, b: &Segment<f64>) -> Option<(f64, f64)> {
     Some((x, y))
 }
 
+/// Finds the vertices of the arrangement of the segments, i.e., every point
+/// where two of them meet, in lexicographic order. Points closer than 1e-9 in
+/// both coordinates are reported once. If two collinear segments overlap, the
+/// ends of the overlap are the vertices.
+///
+/// This is the Bentley-Ottmann sweep, which finds the K vertices in
+/// O((N + K) log N) time. The event points are visited in lexicographic order,
+/// and the segments crossing the sweep line are kept ordered by their height
+/// at the current event point, so that only segments adjacent in that order
+/// need to be checked for crossings.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::line::{arrangement_vertices, Segment};
+/// let segments = [
+///     Segment((0.0, 0.0), (2.0, 2.0)),
+///     Segment((0.0, 2.0), (2.0, 0.0)),
+///     Segment((0.0, 1.0), (3.0, 1.0)),
+/// ];
+/// assert_eq!(arrangement_vertices(&segments), vec![(1.0, 1.0)]);
+/// ```
+// The sweep point in the keys of the status changes, but only in ways that keep
+// the order of the segments in the status
+#[allow(clippy::mutable_key_type)]
+pub fn arrangement_vertices(segments: &[Segment<f64>]) -> Vec<(f64, f64)> {
+    // Orient each segment from its lexicographically smaller endpoint
+    let segments = segments
+        .iter()
+        .map(|&Segment(p, q)| {
+            if cmp_points(p, q) == Ordering::Greater {
+                Segment(q, p)
+            } else {
+                Segment(p, q)
+            }
+        })
+        .collect::<Vec<_>>();
+
+    // The segments starting at each event point
+    let mut events = BTreeMap::<SweepPoint, Vec<usize>>::new();
+    for (id, segment) in segments.iter().enumerate() {
+        events.entry(SweepPoint(segment.0)).or_default().push(id);
+        events.entry(SweepPoint(segment.1)).or_default();
+    }
+
+    let sweep = Cell::new(((0.0, 0.0), true));
+    let key = |id: usize| SweepSegment {
+        segment: segments[id],
+        id,
+        sweep: &sweep,
+    };
+    let mut status = BTreeSet::<SweepSegment>::new();
+    let mut vertices: Vec<(f64, f64)> = Vec::new();
+    while let Some((SweepPoint(p), starting)) = events.pop_first() {
+        // The segments through `p` are contiguous in the order just before it
+        sweep.set((p, true));
+        let probe = SweepSegment {
+            segment: Segment(p, p),
+            id: PROBE,
+            sweep: &sweep,
+        };
+        let through = status
+            .range(probe..)
+            .take_while(|s| passes_through(&s.segment, p))
+            .map(|s| s.id)
+            .collect::<Vec<_>>();
+        if starting.len() + through.len() >= 2 {
+            let duplicate = vertices
+                .iter()
+                .rev()
+                .take_while(|q| p.0 - q.0 <= EPS)
+                .any(|q| (p.1 - q.1).abs() <= EPS);
+            if !duplicate {
+                vertices.push(p);
+            }
+        }
+        for &id in &through {
+            status.remove(&key(id));
+        }
+
+        // Reinsert the segments continuing past `p`, ordered just after it
+        sweep.set((p, false));
+        let ends_at_p = |id: usize| {
+            let q = segments[id].1;
+            (q.0 - p.0).abs().max((q.1 - p.1).abs()) <= tolerance(p)
+        };
+        let mut inserted = starting
+            .into_iter()
+            .chain(through)
+            .filter(|&id| !ends_at_p(id))
+            .map(key)
+            .collect::<Vec<_>>();
+        inserted.sort_unstable();
+        status.extend(inserted.iter().copied());
+
+        let neighbors = match (inserted.first(), inserted.last()) {
+            (Some(lowest), Some(highest)) => [
+                (status.range(..lowest).next_back(), Some(lowest)),
+                (Some(highest), status.range(highest..).nth(1)),
+            ],
+            _ => [
+                (
+                    status.range(..probe).next_back(),
+                    status.range(probe..).next(),
+                ),
+                (None, None),
+            ],
+        };
+        for (a, b) in neighbors {
+            if let (Some(a), Some(b)) = (a, b) {
+                if !do_intersect(a.segment, b.segment) {
+                    continue;
+                }
+                // Collinear overlaps start and end at endpoints, which are
+                // events already
+                if let Some(q) = intersection(&a.segment, &b.segment) {
+                    let tol = tolerance(p);
+                    if q.0 > p.0 + tol || q.0 >= p.0 - tol && q.1 > p.1 + tol {
+                        events.entry(SweepPoint(q)).or_default();
+                    }
+                }
+            }
+        }
+    }
+    vertices
+}
+
+const EPS: f64 = 1e-9;
+
+/// The id of the key in the sweep status placed right before the segments
+/// through the event point.
+const PROBE: usize = usize::MAX;
+
+/// Returns how far apart points near `p` can be to be taken as the same point,
+/// which grows with the coordinates, as the rounding errors do.
+fn tolerance(p: Point<f64>) -> f64 {
+    EPS * (1.0 + p.0.abs().max(p.1.abs()))
+}
+
+fn passes_through(segment: &Segment<f64>, p: Point<f64>) -> bool {
+    point_segment_distance(p, *segment) <= tolerance(p)
+}
+
+fn cmp_points(p: Point<f64>, q: Point<f64>) -> Ordering {
+    cmpf64(p.0, q.0).then(cmpf64(p.1, q.1))
+}
+
+/// An event point of `arrangement_vertices`, ordered lexicographically.
+#[derive(Clone, Copy, Debug)]
+struct SweepPoint(Point<f64>);
+
+impl PartialOrd for SweepPoint {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SweepPoint {
+    fn cmp(&self, other: &Self) -> Ordering {
+        cmp_points(self.0, other.0)
+    }
+}
+
+impl PartialEq for SweepPoint {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SweepPoint {}
+
+/// A segment in the sweep status of `arrangement_vertices`. Like
+/// `ActiveSegment`, but the segments may cross, so they are compared at the
+/// current event point instead of at their starts, which is shared through
+/// `sweep` along with whether the order just before the point is wanted.
+#[derive(Clone, Copy, Debug)]
+struct SweepSegment<'a> {
+    segment: Segment<f64>,
+    id: usize,
+    sweep: &'a Cell<(Point<f64>, bool)>,
+}
+
+/// Returns the height of the segment on the vertical line through `p`, which
+/// for a vertical segment is the point on it closest to `p`.
+fn height(segment: &Segment<f64>, p: Point<f64>) -> f64 {
+    if segment.slope().is_infinite() {
+        let (lo, hi) = (
+            minf64(segment.0 .1, segment.1 .1),
+            maxf64(segment.0 .1, segment.1 .1),
+        );
+        maxf64(lo, minf64(p.1, hi))
+    } else {
+        segment.y(p.0)
+    }
+}
+
+impl PartialOrd for SweepSegment<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for SweepSegment<'_> {
+    /// Segments through the event point are ordered by slope, which is their
+    /// order just after it, or the reverse just before it, after the probe.
+    /// Vertical segments are steeper than any other segment.
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (p, before) = self.sweep.get();
+        if !passes_through(&self.segment, p) || !passes_through(&other.segment, p) {
+            let (a, b) = (height(&self.segment, p), height(&other.segment, p));
+            if a != b {
+                return cmpf64(a, b);
+            }
+        }
+        match (self.id == PROBE, other.id == PROBE) {
+            (true, true) => return Ordering::Equal,
+            (true, false) => return Ordering::Less,
+            (false, true) => return Ordering::Greater,
+            (false, false) => {}
+        }
+        let ord = cmpf64(self.segment.slope(), other.segment.slope());
+        if before { ord.reverse() } else { ord }.then(self.id.cmp(&other.id))
+    }
+}
+
+impl PartialEq for SweepSegment<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for SweepSegment<'_> {}
+
+/// Computes the common point of two segments exactly, as the rationals
+/// `(num_x / den, num_y / den)` with a positive common denominator, reduced so
+/// that the three integers share no common factor. Returns `None` if the
+/// segments do not meet or share more than one point.
+///
+/// The intermediate values are computed in `i128`. The result fits in `i64`
+/// as long as every coordinate is at most 10^6 in absolute value.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::line::{intersection_point_rational, Segment};
+/// let a = Segment((0, 0), (3, 1));
+/// let b = Segment((0, 1), (2, 0));
+/// assert_eq!(intersection_point_rational(a, b), Some(((6, 5), (2, 5))));
+/// ```
+pub fn intersection_point_rational(
+    a: Segment<i64>,
+    b: Segment<i64>,
+) -> Option<((i64, i64), (i64, i64))> {
+    if !do_intersect(a, b) {
+        return None;
+    }
+    let widen = |(x, y): Point<i64>| (i128::from(x), i128::from(y));
+    let (p, q) = (widen(a.0), widen(a.1));
+    let (r, s) = (widen(b.0), widen(b.1));
+    let d1 = (q.0 - p.0, q.1 - p.1);
+    let d2 = (s.0 - r.0, s.1 - r.1);
+
+    let mut den = d1.0 * d2.1 - d1.1 * d2.0;
+    if den == 0 {
+        // Parallel segments that meet must be collinear, so they meet at a
+        // single point only if they touch at an endpoint
+        if relationship_between_segments(a, b) != IntersectionType::MutualEndpoint {
+            return None;
+        }
+        let (x, y) = if a.0 == b.0 || a.0 == b.1 { a.0 } else { a.1 };
+        return Some(((x, 1), (y, 1)));
+    }
+
+    // The point is p + t * d1 with t = num / den
+    let num = (r.0 - p.0) * d2.1 - (r.1 - p.1) * d2.0;
+    let mut x = p.0 * den + d1.0 * num;
+    let mut y = p.1 * den + d1.1 * num;
+    if den < 0 {
+        (x, y, den) = (-x, -y, -den);
+    }
+    let g = gcd(gcd(x.abs(), y.abs()), den);
+    let narrow = |v: i128| i64::try_from(v / g).unwrap();
+    Some(((narrow(x), narrow(den)), (narrow(y), narrow(den))))
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::Segment;
@@ -408,6 +895,55 @@ mod tests {
         assert!(segment1 == 4 && segment2 == 5 || segment1 == 5 && segment2 == 4);
     }
 
+    #[test]
+    fn find_intersecting_segments_degenerate() {
+        let segments: Vec<Segment<f64>> = vec![];
+        assert_eq!(super::find_intersecting_segments(&segments), None);
+        let segments = vec![Segment((0.0, 0.0), (1.0, 1.0))];
+        assert_eq!(super::find_intersecting_segments(&segments), None);
+        let segments = vec![Segment((1.0, 1.0), (1.0, 1.0))];
+        assert_eq!(super::find_intersecting_segments(&segments), None);
+    }
+
+    #[test]
+    fn find_intersecting_segments_i64() {
+        let segments = vec![
+            Segment((1, 0), (9, 0)),
+            Segment((1, 10), (9, 10)),
+            Segment((0, 1), (0, 9)),
+            Segment((10, 1), (10, 9)),
+            Segment((0, 0), (10, 10)),
+            Segment((10, 10), (20, 20)),
+        ];
+        let (segment1, segment2) = super::find_intersecting_segments(&segments).unwrap();
+        assert!(segment1 == 4 && segment2 == 5 || segment1 == 5 && segment2 == 4);
+
+        let segments_f64 = segments
+            .iter()
+            .map(|s| {
+                Segment(
+                    (s.0 .0 as f64, s.0 .1 as f64),
+                    (s.1 .0 as f64, s.1 .1 as f64),
+                )
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            super::find_intersecting_segments(&segments_f64),
+            super::find_intersecting_segments(&segments)
+        );
+
+        let segments = vec![
+            Segment((0, 1 << 30), (1 << 30, 0)),
+            Segment((0, (1 << 30) + 1), (1 << 30, 1)),
+        ];
+        assert_eq!(super::find_intersecting_segments(&segments), None);
+        let segments = vec![
+            Segment((0, 1 << 30), (1 << 30, 0)),
+            Segment((0, (1 << 30) + 1), (1 << 30, 0)),
+        ];
+        assert!(super::find_intersecting_segments(&segments).is_some());
+    }
+
     #[test]
     fn three_lines() {
         let segments = vec![
@@ -601,4 +1137,187 @@ mod tests {
         });
         assert!(result.is_some());
     }
+
+    #[test]
+    fn length_and_unit() {
+        let segment = Segment((-1.0, 2.0), (2.0, -2.0));
+        assert_eq!(segment.length(), 5.0);
+        assert_eq!(segment.unit(), (0.6, -0.8));
+
+        let diagonal = Segment((0.0, 0.0), (3.0, 3.0));
+        assert!((diagonal.length() - 18f64.sqrt()).abs() < 1e-12);
+        let (ux, uy) = diagonal.unit();
+        assert!((ux - 0.5f64.sqrt()).abs() < 1e-12 && (uy - 0.5f64.sqrt()).abs() < 1e-12);
+        assert!((ux.hypot(uy) - 1.0).abs() < 1e-12);
+
+        let point = Segment((1.5, 1.5), (1.5, 1.5));
+        assert_eq!(point.length(), 0.0);
+        assert_eq!(point.unit(), (0.0, 0.0));
+    }
+
+    #[test]
+    fn segment_to_segment_distance() {
+        // Crossing
+        let a = Segment((0.0, 0.0), (2.0, 2.0));
+        let b = Segment((0.0, 2.0), (2.0, 0.0));
+        assert_eq!(super::segment_to_segment_distance(a, b), 0.0);
+
+        // Parallel
+        let a = Segment((0.0, 0.0), (4.0, 0.0));
+        let b = Segment((1.0, 3.0), (5.0, 3.0));
+        assert_eq!(super::segment_to_segment_distance(a, b), 3.0);
+
+        // Skew and not intersecting, closest at an endpoint of one segment
+        let a = Segment((0.0, 0.0), (4.0, 0.0));
+        let b = Segment((6.0, 1.0), (8.0, 5.0));
+        assert_eq!(
+            super::segment_to_segment_distance(a, b),
+            (2.0f64 * 2.0 + 1.0).sqrt()
+        );
+
+        // Skew, closest at the middle of one segment
+        let a = Segment((0.0, 0.0), (4.0, 4.0));
+        let b = Segment((4.0, 0.0), (3.0, 1.0));
+        assert!((super::segment_to_segment_distance(a, b) - 2.0f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn arrangement_vertices() {
+        use super::arrangement_vertices;
+
+        // A triangle meets at its three corners
+        let triangle = [
+            Segment((0.0, 0.0), (4.0, 0.0)),
+            Segment((4.0, 0.0), (0.0, 3.0)),
+            Segment((0.0, 3.0), (0.0, 0.0)),
+        ];
+        assert_eq!(
+            arrangement_vertices(&triangle),
+            vec![(0.0, 0.0), (0.0, 3.0), (4.0, 0.0)]
+        );
+
+        // Three lines through one point, and a collinear overlap
+        let segments = [
+            Segment((0.0, 0.0), (2.0, 2.0)),
+            Segment((0.0, 2.0), (2.0, 0.0)),
+            Segment((1.0, 0.0), (1.0, 2.0)),
+            Segment((1.5, 1.5), (3.0, 3.0)),
+        ];
+        assert_eq!(
+            arrangement_vertices(&segments),
+            vec![(1.0, 1.0), (1.5, 1.5), (2.0, 2.0)]
+        );
+
+        // Random segments on a small grid, with many shared endpoints,
+        // vertical segments and collinear overlaps
+        let naive = |segments: &[Segment<f64>]| {
+            let mut vertices = Vec::new();
+            for (i, &a) in segments.iter().enumerate() {
+                for &b in &segments[i + 1..] {
+                    if !super::do_intersect(a, b) {
+                        continue;
+                    }
+                    match super::intersection(&a, &b) {
+                        Some(p) => vertices.push(p),
+                        None => vertices.extend(
+                            [(a.0, b), (a.1, b), (b.0, a), (b.1, a)]
+                                .into_iter()
+                                .filter(|&(p, seg)| super::point_segment_distance(p, seg) <= 1e-9)
+                                .map(|(p, _)| p),
+                        ),
+                    }
+                }
+            }
+            vertices.sort_unstable_by(|&p, &q| super::cmp_points(p, q));
+            let mut unique: Vec<(f64, f64)> = Vec::new();
+            for p in vertices {
+                if !unique
+                    .iter()
+                    .any(|q| (p.0 - q.0).abs() <= 1e-9 && (p.1 - q.1).abs() <= 1e-9)
+                {
+                    unique.push(p);
+                }
+            }
+            unique
+        };
+        let mut state = 88172645463325252u64;
+        let mut next = |modulus: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % modulus) as f64
+        };
+        // Also large coordinates in general position, with steep segments
+        for modulus in [7, 1_000_000] {
+            for n in [2, 3, 5, 10, 20, 40] {
+                for _ in 0..50 {
+                    let segments = (0..n)
+                        .map(|_| {
+                            Segment(
+                                (next(modulus), next(modulus)),
+                                (next(modulus), next(modulus)),
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    let vertices = arrangement_vertices(&segments);
+                    let expected = naive(&segments);
+                    assert_eq!(vertices.len(), expected.len(), "{:?}", segments);
+                    for (p, q) in vertices.iter().zip(&expected) {
+                        assert!((p.0 - q.0).abs() < 1e-6 && (p.1 - q.1).abs() < 1e-6);
+                    }
+                }
+            }
+        }
+
+        // Nearly coincident crossings are merged
+        let segments = [
+            Segment((0.0, 0.0), (3.0, 3.0)),
+            Segment((0.0, 3.0), (3.0, 0.0)),
+            Segment((1.5 + 1e-12, 0.0), (1.5 + 1e-12, 3.0)),
+            Segment((5.0, 5.0), (6.0, 6.0)),
+        ];
+        assert_eq!(arrangement_vertices(&segments).len(), 1);
+        assert!(arrangement_vertices(&segments[..1]).is_empty());
+    }
+
+    #[test]
+    fn intersection_point_rational() {
+        use super::intersection_point_rational;
+
+        // Crossing at (6/5, 2/5)
+        let a = Segment((0, 0), (3, 1));
+        let b = Segment((0, 1), (2, 0));
+        assert_eq!(intersection_point_rational(a, b), Some(((6, 5), (2, 5))));
+        assert_eq!(intersection_point_rational(b, a), Some(((6, 5), (2, 5))));
+
+        // Reduced to integers, and with negative coordinates
+        let a = Segment((-4, -4), (4, 4));
+        let b = Segment((-4, 4), (4, -4));
+        assert_eq!(intersection_point_rational(a, b), Some(((0, 1), (0, 1))));
+        let a = Segment((-3, 0), (1, -2));
+        let b = Segment((-3, -2), (0, 0));
+        assert_eq!(intersection_point_rational(a, b), Some(((-9, 7), (-6, 7))));
+
+        // Touching at an endpoint
+        let a = Segment((0, 0), (2, 2));
+        let b = Segment((2, 2), (5, 0));
+        assert_eq!(intersection_point_rational(a, b), Some(((2, 1), (2, 1))));
+        let b = Segment((4, 4), (2, 2));
+        assert_eq!(intersection_point_rational(a, b), Some(((2, 1), (2, 1))));
+
+        // Disjoint, overlapping, and parallel
+        let b = Segment((3, 0), (5, 0));
+        assert_eq!(intersection_point_rational(a, b), None);
+        let b = Segment((1, 1), (3, 3));
+        assert_eq!(intersection_point_rational(a, b), None);
+        let b = Segment((0, 1), (2, 3));
+        assert_eq!(intersection_point_rational(a, b), None);
+
+        // The largest coordinates still fit
+        let m = 1_000_000;
+        let a = Segment((-m, -m), (m, m - 1));
+        let b = Segment((-m, m), (m, -m + 1));
+        let ((x, den), (y, _)) = intersection_point_rational(a, b).unwrap();
+        assert!(x.abs() <= den && y.abs() <= den);
+    }
 }
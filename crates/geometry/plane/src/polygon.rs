@@ -0,0 +1,633 @@
+use std::cmp::Ordering;
+
+use convex_hull::diameter_pair;
+
+use crate::{
+    cmpf64,
+    line::{
+        cross_product, do_intersect, find_intersecting_segments_by, intersection,
+        point_segment_distance, relationship_between_segments, IntersectionType, Segment,
+    },
+};
+
+/// Clips a polygon to one side of the infinite line through `line`, keeping the
+/// part to the left of the line if `keep_left` is true and the part to the
+/// right otherwise. Vertices on the line are kept.
+///
+/// This is a single step of the Sutherland-Hodgman algorithm, so clipping by
+/// several half-planes in turn yields the intersection with their convex
+/// region.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::{line::Segment, polygon::clip_half_plane};
+/// let square = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+/// let clipped = clip_half_plane(&square, Segment((0.0, 1.0), (1.0, 1.0)), true);
+/// assert_eq!(clipped, vec![(2.0, 1.0), (2.0, 2.0), (0.0, 2.0), (0.0, 1.0)]);
+/// ```
+pub fn clip_half_plane(
+    polygon: &[(f64, f64)],
+    line: Segment<f64>,
+    keep_left: bool,
+) -> Vec<(f64, f64)> {
+    let side = |p| {
+        let cross = cross_product(line.0, line.1, p);
+        if keep_left {
+            cross
+        } else {
+            -cross
+        }
+    };
+
+    let mut clipped = Vec::new();
+    for (i, &p) in polygon.iter().enumerate() {
+        let q = polygon[(i + 1) % polygon.len()];
+        let (side_p, side_q) = (side(p), side(q));
+        if side_p >= 0.0 {
+            clipped.push(p);
+        }
+        if side_p > 0.0 && side_q < 0.0 || side_p < 0.0 && side_q > 0.0 {
+            if let Some(crossing) = intersection(&Segment(p, q), &line) {
+                clipped.push(crossing);
+            }
+        }
+    }
+    clipped
+}
+
+/// Checks if `p` lies on an edge of the polygon, including its vertices,
+/// within a distance of 1e-9.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::polygon::on_boundary;
+/// let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+/// assert!(on_boundary(&square, (1.0, 0.0)));
+/// assert!(!on_boundary(&square, (1.0, 1.0)));
+/// ```
+pub fn on_boundary(polygon: &[(f64, f64)], p: (f64, f64)) -> bool {
+    let n = polygon.len();
+    (0..n).any(|i| point_segment_distance(p, Segment(polygon[i], polygon[(i + 1) % n])) <= 1e-9)
+}
+
+/// Checks if `p` lies inside the polygon with the even-odd rule, counting the
+/// edges crossed by a ray from `p` towards positive x. Points on the boundary
+/// may be reported either way; use `on_boundary` to detect them.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::polygon::point_in_polygon;
+/// let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+/// assert!(point_in_polygon(&square, (1.0, 1.0)));
+/// assert!(!point_in_polygon(&square, (3.0, 1.0)));
+/// ```
+pub fn point_in_polygon(polygon: &[(f64, f64)], p: (f64, f64)) -> bool {
+    let n = polygon.len();
+    let mut inside = false;
+    for i in 0..n {
+        let (a, b) = (polygon[i], polygon[(i + 1) % n]);
+        if (a.1 > p.1) != (b.1 > p.1) {
+            let x = a.0 + (p.1 - a.1) * (b.0 - a.0) / (b.1 - a.1);
+            if p.0 < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// Checks if a segment meets a polygon, that is, it crosses or touches the
+/// boundary or lies inside the polygon.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::{line::Segment, polygon::segment_intersects_polygon};
+/// let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+/// assert!(segment_intersects_polygon(&square, Segment((1.0, 1.0), (3.0, 1.0))));
+/// assert!(!segment_intersects_polygon(&square, Segment((3.0, 0.0), (3.0, 2.0))));
+/// ```
+pub fn segment_intersects_polygon(polygon: &[(f64, f64)], seg: Segment<f64>) -> bool {
+    let n = polygon.len();
+    (0..n).any(|i| do_intersect(seg, Segment(polygon[i], polygon[(i + 1) % n])))
+        || point_in_polygon(polygon, seg.0)
+}
+
+/// Checks if a polygon is simple, that is, no two edges meet except adjacent
+/// edges at their shared vertex. Polygons with fewer than three vertices are
+/// not simple.
+///
+/// Runs in O(N log N) with a sweep line over the edges.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::polygon::is_simple;
+/// assert!(is_simple(&[(0.0, 0.0), (1.0, 0.0), (1.0, 1.0), (0.0, 1.0)]));
+/// assert!(!is_simple(&[(0.0, 0.0), (1.0, 1.0), (1.0, 0.0), (0.0, 1.0)]));
+/// ```
+pub fn is_simple(polygon: &[(f64, f64)]) -> bool {
+    if polygon.len() < 3 {
+        return false;
+    }
+
+    // The sweep ignores edges meeting at endpoints, so a vertex visited twice
+    // has to be found separately.
+    let mut vertices = polygon.to_vec();
+    vertices.sort_unstable_by(|a, b| cmpf64(a.0, b.0).then(cmpf64(a.1, b.1)));
+    if vertices.windows(2).any(|w| w[0] == w[1]) {
+        return false;
+    }
+
+    let edges = (0..polygon.len())
+        .map(|i| Segment(polygon[i], polygon[(i + 1) % polygon.len()]))
+        .collect::<Vec<_>>();
+    find_intersecting_segments_by(&edges, |a, b| {
+        matches!(
+            relationship_between_segments(a, b),
+            IntersectionType::Proper | IntersectionType::Collinear | IntersectionType::OneSided
+        )
+    })
+    .is_none()
+}
+
+/// Finds the farthest pair of vertices of a polygon, which need not be
+/// convex, and returns their indices in `polygon`. The pair is found among the
+/// antipodal pairs of the convex hull of the vertices in O(N log N).
+///
+/// # Panics
+///
+/// Panics if `polygon` is empty.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::polygon::polygon_diameter;
+/// let polygon = [(0, 0), (2, 1), (4, 0), (4, 3), (0, 3)];
+/// let (i, j) = polygon_diameter(&polygon);
+/// assert!((i, j) == (0, 3) || (i, j) == (3, 0) || (i, j) == (2, 4) || (i, j) == (4, 2));
+/// ```
+pub fn polygon_diameter(polygon: &[(i64, i64)]) -> (usize, usize) {
+    diameter_pair(polygon).expect("empty polygon").0
+}
+
+/// Determines on which side of the infinite line through `line` a convex
+/// polygon lies, from the extreme vertices of the polygon in the two normal
+/// directions of the line. Returns `Ordering::Greater` if the polygon is
+/// strictly to the left, `Ordering::Less` if it is strictly to the right, and
+/// `Ordering::Equal` if it touches or crosses the line.
+///
+/// The vertices have to be in counterclockwise order without duplicates, as
+/// `convex_hull` produces them, and each extreme vertex is found by binary
+/// search on the directions of the edges in O(log N) time.
+///
+/// # Panics
+///
+/// Panics if `hull` is empty.
+///
+/// # Examples
+///
+/// ```
+/// # use std::cmp::Ordering;
+/// # use plane::{line::Segment, polygon::polygon_side_of_line};
+/// let square = [(1, 1), (2, 1), (2, 2), (1, 2)];
+/// let line = Segment((0, 0), (1, 0));
+/// assert_eq!(polygon_side_of_line(&square, line), Ordering::Greater);
+/// ```
+pub fn polygon_side_of_line(hull: &[(i64, i64)], line: Segment<i64>) -> Ordering {
+    assert!(!hull.is_empty(), "empty polygon");
+    let side = |i: usize| cross_product(line.0, line.1, hull[i]);
+    let direction = (line.1 .0 - line.0 .0, line.1 .1 - line.0 .1);
+    let (min, max) = if hull.len() < 3 || direction == (0, 0) {
+        let sides = (0..hull.len()).map(side);
+        (sides.clone().min().unwrap(), sides.max().unwrap())
+    } else {
+        // The side grows along the edges turning left of the line, so it is
+        // largest where the edges turn past the opposite direction
+        let opposite = (-direction.0, -direction.1);
+        (
+            side(extreme_vertex(hull, direction)),
+            side(extreme_vertex(hull, opposite)),
+        )
+    };
+    if min > 0 {
+        Ordering::Greater
+    } else if max < 0 {
+        Ordering::Less
+    } else {
+        Ordering::Equal
+    }
+}
+
+/// Finds the vertex of a counterclockwise convex polygon with at least three
+/// vertices where the edges, turning counterclockwise from the first one,
+/// reach the direction `target`.
+fn extreme_vertex(hull: &[(i64, i64)], target: (i64, i64)) -> usize {
+    let n = hull.len();
+    let widen = |(x, y): (i64, i64)| (i128::from(x), i128::from(y));
+    let edge = |i: usize| {
+        let (p, q) = (widen(hull[i]), widen(hull[(i + 1) % n]));
+        (q.0 - p.0, q.1 - p.1)
+    };
+    let cross = |u: (i128, i128), w: (i128, i128)| u.0 * w.1 - u.1 * w.0;
+    // The angle from the first edge is in [0, pi) in half 0, and [pi, 2 pi) in
+    // half 1
+    let first = edge(0);
+    let half = |u: (i128, i128)| {
+        let c = cross(first, u);
+        usize::from(c < 0 || c == 0 && first.0 * u.0 + first.1 * u.1 < 0)
+    };
+    let target = widen(target);
+    let before_target =
+        |u| half(u) < half(target) || half(u) == half(target) && cross(u, target) > 0;
+
+    let (mut lo, mut hi) = (0, n);
+    while lo < hi {
+        let mid = (lo + hi) / 2;
+        if before_target(edge(mid)) {
+            lo = mid + 1;
+        } else {
+            hi = mid;
+        }
+    }
+    lo % n
+}
+
+/// Counts the lattice points on the boundary of a polygon, which is the sum of
+/// `gcd(|dx|, |dy|)` over its edges. Along with the area, this gives the
+/// number of interior lattice points by Pick's theorem.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::polygon::boundary_lattice_points;
+/// let triangle = [(0, 0), (4, 0), (0, 6)];
+/// assert_eq!(boundary_lattice_points(&triangle), 12);
+/// ```
+pub fn boundary_lattice_points(polygon: &[(i64, i64)]) -> i64 {
+    let n = polygon.len();
+    (0..n)
+        .map(|i| {
+            let (p, q) = (polygon[i], polygon[(i + 1) % n]);
+            gcd((q.0 - p.0).abs(), (q.1 - p.1).abs())
+        })
+        .sum()
+}
+
+fn gcd(a: i64, b: i64) -> i64 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Computes the area of the union of axis-aligned rectangles, each given by
+/// two opposite corners, in O(N log N). A vertical line sweeps over the
+/// rectangles while a segment tree over the compressed y-coordinates keeps
+/// the length of the line covered by the rectangles.
+///
+/// # Examples
+///
+/// ```
+/// # use plane::polygon::rectangles_union_area;
+/// let rects = [((0, 0), (2, 2)), ((1, 1), (3, 3))];
+/// assert_eq!(rectangles_union_area(&rects), 7);
+/// ```
+#[allow(clippy::type_complexity)]
+pub fn rectangles_union_area(rects: &[((i64, i64), (i64, i64))]) -> i64 {
+    let mut ys = Vec::with_capacity(2 * rects.len());
+    let mut events = Vec::with_capacity(2 * rects.len());
+    for &((x1, y1), (x2, y2)) in rects {
+        let (x1, x2) = (x1.min(x2), x1.max(x2));
+        let (y1, y2) = (y1.min(y2), y1.max(y2));
+        if x1 == x2 || y1 == y2 {
+            continue;
+        }
+        ys.extend([y1, y2]);
+        events.push((x1, 1, y1, y2));
+        events.push((x2, -1, y1, y2));
+    }
+    ys.sort_unstable();
+    ys.dedup();
+    events.sort_unstable();
+    if ys.len() < 2 {
+        return 0;
+    }
+
+    let mut cover = CoverTree {
+        ys: &ys,
+        count: vec![0; 4 * ys.len()],
+        covered: vec![0; 4 * ys.len()],
+    };
+    let mut area = 0;
+    let mut prev_x = events.first().map_or(0, |e| e.0);
+    for (x, delta, y1, y2) in events {
+        area += cover.covered[1] * (x - prev_x);
+        prev_x = x;
+        let l = ys.binary_search(&y1).unwrap();
+        let r = ys.binary_search(&y2).unwrap();
+        cover.update(1, 0, ys.len() - 1, l, r, delta);
+    }
+    area
+}
+
+/// A segment tree over the elementary intervals between consecutive `ys`,
+/// keeping how many rectangles cover each node entirely and the total covered
+/// length under it.
+struct CoverTree<'a> {
+    ys: &'a [i64],
+    count: Vec<i32>,
+    covered: Vec<i64>,
+}
+
+impl CoverTree<'_> {
+    /// Adds `delta` to the cover count of the intervals in `[l, r)` within the
+    /// node covering `[lo, hi)`.
+    fn update(&mut self, node: usize, lo: usize, hi: usize, l: usize, r: usize, delta: i32) {
+        if r <= lo || hi <= l {
+            return;
+        }
+        if l <= lo && hi <= r {
+            self.count[node] += delta;
+        } else {
+            let mid = (lo + hi) / 2;
+            self.update(2 * node, lo, mid, l, r, delta);
+            self.update(2 * node + 1, mid, hi, l, r, delta);
+        }
+        self.covered[node] = if self.count[node] > 0 {
+            self.ys[hi] - self.ys[lo]
+        } else if hi - lo == 1 {
+            0
+        } else {
+            self.covered[2 * node] + self.covered[2 * node + 1]
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::line::Segment;
+
+    #[test]
+    fn clip_half_plane() {
+        let square = vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+        let diagonal = Segment((0.0, 0.0), (2.0, 2.0));
+
+        let left = super::clip_half_plane(&square, diagonal, true);
+        assert_eq!(left, vec![(0.0, 0.0), (2.0, 2.0), (0.0, 2.0)]);
+
+        let right = super::clip_half_plane(&square, diagonal, false);
+        assert_eq!(right, vec![(0.0, 0.0), (2.0, 0.0), (2.0, 2.0)]);
+
+        let outside = Segment((3.0, 0.0), (3.0, 1.0));
+        assert!(super::clip_half_plane(&square, outside, false).is_empty());
+        assert_eq!(super::clip_half_plane(&square, outside, true), square);
+    }
+
+    #[test]
+    fn on_boundary() {
+        let polygon = [(0.0, 0.0), (4.0, 0.0), (4.0, 3.0), (2.0, 5.0), (0.0, 3.0)];
+        // Edge interiors, including the closing edge and a slanted edge
+        assert!(super::on_boundary(&polygon, (2.5, 0.0)));
+        assert!(super::on_boundary(&polygon, (0.0, 1.5)));
+        assert!(super::on_boundary(&polygon, (3.0, 4.0)));
+        assert!(super::on_boundary(&polygon, (0.3, 3.3)));
+        // Vertices
+        for &v in &polygon {
+            assert!(super::on_boundary(&polygon, v));
+        }
+        // Inside, outside, and on the extension of an edge
+        assert!(!super::on_boundary(&polygon, (2.0, 2.0)));
+        assert!(!super::on_boundary(&polygon, (5.0, 1.0)));
+        assert!(!super::on_boundary(&polygon, (5.0, 0.0)));
+        assert!(!super::on_boundary(&polygon, (2.0, 0.001)));
+        assert!(!super::on_boundary(&[], (0.0, 0.0)));
+    }
+
+    #[test]
+    fn point_in_polygon() {
+        // A concave polygon whose notch is level with some vertices
+        let polygon = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (2.0, 2.0), (0.0, 4.0)];
+        assert!(super::point_in_polygon(&polygon, (1.0, 1.0)));
+        assert!(super::point_in_polygon(&polygon, (0.5, 3.0)));
+        assert!(super::point_in_polygon(&polygon, (1.0, 2.0)));
+        assert!(!super::point_in_polygon(&polygon, (2.0, 3.0)));
+        assert!(!super::point_in_polygon(&polygon, (-1.0, 2.0)));
+        assert!(!super::point_in_polygon(&polygon, (5.0, 4.0)));
+        assert!(!super::point_in_polygon(&[], (0.0, 0.0)));
+    }
+
+    #[test]
+    fn segment_intersects_polygon() {
+        use crate::line::Segment;
+
+        let polygon = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (2.0, 2.0), (0.0, 4.0)];
+        // Crossing an edge
+        assert!(super::segment_intersects_polygon(
+            &polygon,
+            Segment((-1.0, 1.0), (1.0, 1.0))
+        ));
+        // Passing through the polygon with both endpoints outside
+        assert!(super::segment_intersects_polygon(
+            &polygon,
+            Segment((-1.0, 1.0), (5.0, 1.0))
+        ));
+        // Fully inside
+        assert!(super::segment_intersects_polygon(
+            &polygon,
+            Segment((1.0, 1.0), (3.0, 1.0))
+        ));
+        // Touching a vertex
+        assert!(super::segment_intersects_polygon(
+            &polygon,
+            Segment((2.0, 2.0), (2.0, 3.0))
+        ));
+        // Fully outside, inside the notch
+        assert!(!super::segment_intersects_polygon(
+            &polygon,
+            Segment((1.5, 3.5), (2.5, 3.5))
+        ));
+        // Fully outside, beyond the polygon
+        assert!(!super::segment_intersects_polygon(
+            &polygon,
+            Segment((5.0, 0.0), (6.0, 5.0))
+        ));
+    }
+
+    #[test]
+    fn is_simple() {
+        let square = [(0.0, 0.0), (2.0, 0.0), (2.0, 2.0), (0.0, 2.0)];
+        assert!(super::is_simple(&square));
+
+        let bowtie = [(0.0, 0.0), (2.0, 2.0), (2.0, 0.0), (0.0, 2.0)];
+        assert!(!super::is_simple(&bowtie));
+
+        let arrow = [(0.0, 0.0), (4.0, 2.0), (0.0, 4.0), (1.0, 2.0)];
+        assert!(super::is_simple(&arrow));
+
+        // Touches itself at a vertex lying on another edge
+        let pinched = [(0.0, 0.0), (4.0, 0.0), (4.0, 4.0), (2.0, 0.0), (0.0, 4.0)];
+        assert!(!super::is_simple(&pinched));
+
+        // Visits the same vertex twice
+        let figure_eight = [
+            (0.0, 0.0),
+            (1.0, 1.0),
+            (2.0, 0.0),
+            (2.0, 2.0),
+            (1.0, 1.0),
+            (0.0, 2.0),
+        ];
+        assert!(!super::is_simple(&figure_eight));
+
+        // Doubles back along an edge
+        let folded = [(0.0, 0.0), (2.0, 0.0), (1.0, 0.0), (1.0, 1.0)];
+        assert!(!super::is_simple(&folded));
+
+        assert!(!super::is_simple(&[(0.0, 0.0), (1.0, 0.0)]));
+    }
+
+    #[test]
+    fn polygon_diameter() {
+        // An arrow whose notch vertex (1, 2) is inside the hull
+        let polygon = [(1, 2), (0, 0), (6, 2), (0, 4)];
+        let (i, j) = super::polygon_diameter(&polygon);
+        assert!((i, j) == (1, 2) || (i, j) == (2, 1) || (i, j) == (2, 3) || (i, j) == (3, 2));
+
+        let polygon = [(0, 0), (5, 1), (1, 1), (2, 7), (3, 2), (-1, 3)];
+        let (i, j) = super::polygon_diameter(&polygon);
+        assert!((i, j) == (0, 3) || (i, j) == (3, 0));
+
+        assert_eq!(super::polygon_diameter(&[(1, 1)]), (0, 0));
+        let (i, j) = super::polygon_diameter(&[(1, 1), (1, 1)]);
+        assert!(i < 2 && j < 2);
+        let (i, j) = super::polygon_diameter(&[(0, 0), (1, 1), (3, 3), (2, 2)]);
+        assert!((i, j) == (0, 2) || (i, j) == (2, 0));
+    }
+
+    #[test]
+    fn polygon_side_of_line() {
+        use std::cmp::Ordering;
+
+        let hull = [(2, 0), (4, 1), (3, 3), (1, 2)];
+        let side = |line| super::polygon_side_of_line(&hull, line);
+        assert_eq!(side(Segment((0, 0), (0, 1))), Ordering::Less);
+        assert_eq!(side(Segment((0, 1), (0, 0))), Ordering::Greater);
+        assert_eq!(side(Segment((0, 5), (5, 0))), Ordering::Equal);
+        assert_eq!(side(Segment((0, 4), (4, 4))), Ordering::Less);
+        assert_eq!(side(Segment((0, 3), (4, 3))), Ordering::Equal);
+        assert_eq!(side(Segment((5, 0), (6, 2))), Ordering::Greater);
+        assert_eq!(side(Segment((0, 0), (1, 1))), Ordering::Equal);
+    }
+
+    #[test]
+    fn polygon_side_of_line_random() {
+        use std::cmp::Ordering;
+
+        let mut state = 88172645463325252u64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 21) as i64 - 10
+        };
+        for n in [1, 2, 3, 5, 10, 50] {
+            for include_midpoints in [false, true] {
+                let mut points = (0..n).map(|_| (next(), next())).collect::<Vec<_>>();
+                points.sort_unstable();
+                points.dedup();
+                let hull = convex_hull::convex_hull_points(points, include_midpoints);
+                if hull.is_empty() {
+                    continue;
+                }
+                for _ in 0..100 {
+                    let line = Segment((next(), next()), (next(), next()));
+                    let sides = hull
+                        .iter()
+                        .map(|&p| super::cross_product(line.0, line.1, p))
+                        .collect::<Vec<_>>();
+                    let expected = if sides.iter().all(|&s| s > 0) {
+                        Ordering::Greater
+                    } else if sides.iter().all(|&s| s < 0) {
+                        Ordering::Less
+                    } else {
+                        Ordering::Equal
+                    };
+                    assert_eq!(super::polygon_side_of_line(&hull, line), expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "empty polygon")]
+    fn polygon_side_of_line_empty() {
+        super::polygon_side_of_line(&[], Segment((0, 0), (1, 0)));
+    }
+
+    #[test]
+    fn boundary_lattice_points() {
+        // A lattice point of each edge, besides the start, lies on the boundary
+        let polygon = [(0, 0), (6, 0), (6, 4), (3, 7), (-3, 1)];
+        assert_eq!(super::boundary_lattice_points(&polygon), 6 + 4 + 3 + 6 + 1);
+        let float = polygon.map(|(x, y)| (x as f64, y as f64));
+        let (mut boundary, mut interior) = (0, 0);
+        for x in -3..=6 {
+            for y in 0..=7 {
+                let p = (x as f64, y as f64);
+                if super::on_boundary(&float, p) {
+                    boundary += 1;
+                } else if super::point_in_polygon(&float, p) {
+                    interior += 1;
+                }
+            }
+        }
+        assert_eq!(super::boundary_lattice_points(&polygon), boundary);
+
+        // Pick's theorem gives the interior points from twice the area
+        let area2 = 78;
+        assert_eq!((area2 - boundary + 2) / 2, interior);
+
+        assert_eq!(super::boundary_lattice_points(&[(1, 1)]), 0);
+        assert_eq!(super::boundary_lattice_points(&[]), 0);
+    }
+
+    #[test]
+    fn rectangles_union_area() {
+        let rects = [((0, 0), (4, 3)), ((2, 1), (6, 5))];
+        assert_eq!(super::rectangles_union_area(&rects), 12 + 16 - 4);
+
+        // Corners in any order, a nested rectangle, and a degenerate one
+        let rects = [
+            ((4, 3), (0, 0)),
+            ((1, 1), (2, 2)),
+            ((10, 10), (10, 20)),
+            ((-2, 5), (1, -1)),
+        ];
+        assert_eq!(super::rectangles_union_area(&rects), 12 + 18 - 3);
+
+        assert_eq!(super::rectangles_union_area(&[]), 0);
+
+        // Compare against counting unit cells
+        let rects = [
+            ((0, 0), (3, 7)),
+            ((1, 2), (6, 4)),
+            ((5, 0), (8, 1)),
+            ((2, 5), (4, 9)),
+            ((7, 3), (9, 9)),
+        ];
+        let cells = (0..10)
+            .flat_map(|x| (0..10).map(move |y| (x, y)))
+            .filter(|&(x, y)| {
+                rects
+                    .iter()
+                    .any(|&((x1, y1), (x2, y2))| x1 <= x && x < x2 && y1 <= y && y < y2)
+            })
+            .count();
+        assert_eq!(super::rectangles_union_area(&rects), cells as i64);
+    }
+}
@@ -1,17 +1,60 @@
 use std::{
     cmp::{Ordering, Reverse},
-    collections::BinaryHeap,
+    collections::{BinaryHeap, VecDeque},
     iter::{self, Product, Sum},
     ops::{Add, AddAssign},
 };
 
-pub fn costs<V, Es, Vs, Ws, WsI, W>(start: V, mut neighbors: Es, mut costs: Ws, mut cost: WsI) -> Ws
+/// Computes the minimum cost from `start` to each vertex with Dijkstra's
+/// algorithm, storing them in `costs` through `cost`, which has to give a
+/// cost greater than any path cost for the vertices not reached yet.
+///
+/// Stale heap entries are skipped before expanding them, so `neighbors` is
+/// called exactly once for each reachable vertex, when its cost is final. An
+/// expensive or lazily generated `neighbors` therefore does no wasted work.
+pub fn costs<V, Es, Vs, Ws, WsI, W>(start: V, neighbors: Es, costs: Ws, cost: WsI) -> Ws
 where
     V: Copy + Ord,
     Es: FnMut(V) -> Vs,
     Vs: IntoIterator<Item = (V, W)>,
     WsI: FnMut(&mut Ws, V) -> &mut W,
     W: Copy + Ord + Add<Output = W> + Sum,
+{
+    costs_with_visitor(start, neighbors, costs, cost, |_, _| {})
+}
+
+/// Same as `costs`, but calls `on_settle` with each reachable vertex and its
+/// minimum cost once the cost is final, in non-decreasing order of cost.
+///
+/// # Examples
+///
+/// ```
+/// # use dijkstra::costs_with_visitor;
+/// let adj = vec![vec![(1, 5), (2, 1)], vec![], vec![(1, 1)]];
+/// let mut settled = Vec::new();
+/// costs_with_visitor(
+///     0,
+///     |v| adj[v].iter().copied(),
+///     vec![u32::MAX; 3],
+///     |costs: &mut Vec<u32>, v| &mut costs[v],
+///     |v, c| settled.push((v, c)),
+/// );
+/// assert_eq!(settled, vec![(0, 0), (2, 1), (1, 2)]);
+/// ```
+pub fn costs_with_visitor<V, Es, Vs, Ws, WsI, W, F>(
+    start: V,
+    mut neighbors: Es,
+    mut costs: Ws,
+    mut cost: WsI,
+    mut on_settle: F,
+) -> Ws
+where
+    V: Copy + Ord,
+    Es: FnMut(V) -> Vs,
+    Vs: IntoIterator<Item = (V, W)>,
+    WsI: FnMut(&mut Ws, V) -> &mut W,
+    W: Copy + Ord + Add<Output = W> + Sum,
+    F: FnMut(V, W),
 {
     *cost(&mut costs, start) = iter::empty().sum();
     let queue = &mut BinaryHeap::from(vec![(Reverse(iter::empty().sum()), start)]);
@@ -19,6 +62,7 @@ where
         if *cost(&mut costs, current_node) < current_cost {
             continue;
         }
+        on_settle(current_node, current_cost);
         for (next_node, cost_delta) in neighbors(current_node) {
             let next_cost = current_cost + cost_delta;
             if next_cost < *cost(&mut costs, next_node) {
@@ -31,12 +75,46 @@ where
 }
 
 pub fn costs_and_counts<V, Es, Vs, Ws, WsI, W, Cs, CsI, C>(
+    start: V,
+    neighbors: Es,
+    costs: Ws,
+    cost: WsI,
+    counts: Cs,
+    count: CsI,
+) -> (Ws, Cs)
+where
+    V: Copy + Ord,
+    Es: FnMut(V) -> Vs,
+    Vs: IntoIterator<Item = (V, W)>,
+    WsI: FnMut(&mut Ws, V) -> &mut W,
+    W: Copy + Ord + Add<Output = W> + Sum,
+    CsI: FnMut(&mut Cs, V) -> &mut C,
+    C: Copy + AddAssign + Product,
+{
+    costs_and_counts_by(
+        start,
+        neighbors,
+        costs,
+        cost,
+        counts,
+        count,
+        iter::empty().product(),
+        |next_count, current_count| *next_count += current_count,
+    )
+}
+
+/// The loop shared by `costs_and_counts` and `costs_and_counts_mod`, where
+/// the path count of `start` is `one` and `add` adds a count into another.
+#[allow(clippy::too_many_arguments)]
+fn costs_and_counts_by<V, Es, Vs, Ws, WsI, W, Cs, CsI, C, A>(
     start: V,
     mut neighbors: Es,
     mut costs: Ws,
     mut cost: WsI,
     mut counts: Cs,
     mut count: CsI,
+    one: C,
+    mut add: A,
 ) -> (Ws, Cs)
 where
     V: Copy + Ord,
@@ -45,10 +123,11 @@ where
     WsI: FnMut(&mut Ws, V) -> &mut W,
     W: Copy + Ord + Add<Output = W> + Sum,
     CsI: FnMut(&mut Cs, V) -> &mut C,
-    C: Copy + AddAssign + Product,
+    C: Copy,
+    A: FnMut(&mut C, C),
 {
     *cost(&mut costs, start) = iter::empty().sum();
-    *count(&mut counts, start) = iter::empty().product();
+    *count(&mut counts, start) = one;
     let queue = &mut BinaryHeap::from(vec![(Reverse(iter::empty().sum()), start)]);
     while let Some((Reverse(current_cost), current_node)) = queue.pop() {
         if *cost(&mut costs, current_node) < current_cost {
@@ -64,7 +143,7 @@ where
                 }
                 Ordering::Equal => {
                     let current_count = *count(&mut counts, current_node);
-                    *count(&mut counts, next_node) += current_count;
+                    add(count(&mut counts, next_node), current_count);
                 }
                 Ordering::Greater => {}
             }
@@ -72,3 +151,632 @@ where
     }
     (costs, counts)
 }
+
+/// Computes the minimum costs like `costs`, and for each vertex the minimum
+/// number of edges on a path of that cost.
+///
+/// # Examples
+///
+/// ```
+/// # use dijkstra::costs_and_edge_counts;
+/// let adj = vec![vec![(1, 1), (2, 2)], vec![(2, 1)], vec![]];
+/// let (costs, edge_counts) = costs_and_edge_counts(
+///     0,
+///     |v| adj[v].iter().copied(),
+///     vec![u32::MAX; 3],
+///     |costs: &mut Vec<u32>, v| &mut costs[v],
+///     vec![usize::MAX; 3],
+///     |edge_counts: &mut Vec<usize>, v| &mut edge_counts[v],
+/// );
+/// assert_eq!(costs, vec![0, 1, 2]);
+/// assert_eq!(edge_counts, vec![0, 1, 1]);
+/// ```
+pub fn costs_and_edge_counts<V, Es, Vs, Ws, WsI, W, Hs, HsI>(
+    start: V,
+    mut neighbors: Es,
+    mut costs: Ws,
+    mut cost: WsI,
+    mut edge_counts: Hs,
+    mut edge_count: HsI,
+) -> (Ws, Hs)
+where
+    V: Copy + Ord,
+    Es: FnMut(V) -> Vs,
+    Vs: IntoIterator<Item = (V, W)>,
+    WsI: FnMut(&mut Ws, V) -> &mut W,
+    W: Copy + Ord + Add<Output = W> + Sum,
+    HsI: FnMut(&mut Hs, V) -> &mut usize,
+{
+    *cost(&mut costs, start) = iter::empty().sum();
+    *edge_count(&mut edge_counts, start) = 0;
+    let queue = &mut BinaryHeap::from(vec![(Reverse((iter::empty().sum(), 0)), start)]);
+    while let Some((Reverse((current_cost, current_edges)), current_node)) = queue.pop() {
+        if (
+            *cost(&mut costs, current_node),
+            *edge_count(&mut edge_counts, current_node),
+        ) < (current_cost, current_edges)
+        {
+            continue;
+        }
+        for (next_node, cost_delta) in neighbors(current_node) {
+            let next = (current_cost + cost_delta, current_edges + 1);
+            if next
+                < (
+                    *cost(&mut costs, next_node),
+                    *edge_count(&mut edge_counts, next_node),
+                )
+            {
+                *cost(&mut costs, next_node) = next.0;
+                *edge_count(&mut edge_counts, next_node) = next.1;
+                queue.push((Reverse(next), next_node));
+            }
+        }
+    }
+    (costs, edge_counts)
+}
+
+/// Same as `costs_and_counts`, but counts the shortest paths modulo `modulus`.
+///
+/// # Panics
+///
+/// Panics if `modulus` is zero.
+///
+/// # Examples
+///
+/// ```
+/// # use dijkstra::costs_and_counts_mod;
+/// let adj = vec![vec![(1, 1), (2, 1)], vec![(3, 1)], vec![(3, 1)], vec![]];
+/// let (costs, counts) = costs_and_counts_mod(
+///     0,
+///     |v| adj[v].iter().copied(),
+///     vec![u32::MAX; 4],
+///     |costs: &mut Vec<u32>, v| &mut costs[v],
+///     vec![0; 4],
+///     |counts: &mut Vec<u64>, v| &mut counts[v],
+///     1_000_000_007,
+/// );
+/// assert_eq!(costs, vec![0, 1, 1, 2]);
+/// assert_eq!(counts, vec![1, 1, 1, 2]);
+/// ```
+pub fn costs_and_counts_mod<V, Es, Vs, Ws, WsI, W, Cs, CsI>(
+    start: V,
+    neighbors: Es,
+    costs: Ws,
+    cost: WsI,
+    counts: Cs,
+    count: CsI,
+    modulus: u64,
+) -> (Ws, Cs)
+where
+    V: Copy + Ord,
+    Es: FnMut(V) -> Vs,
+    Vs: IntoIterator<Item = (V, W)>,
+    WsI: FnMut(&mut Ws, V) -> &mut W,
+    W: Copy + Ord + Add<Output = W> + Sum,
+    CsI: FnMut(&mut Cs, V) -> &mut u64,
+{
+    assert!(modulus > 0, "the modulus must be positive");
+    costs_and_counts_by(
+        start,
+        neighbors,
+        costs,
+        cost,
+        counts,
+        count,
+        1 % modulus,
+        |next_count, current_count| {
+            *next_count = ((u128::from(*next_count) + u128::from(current_count))
+                % u128::from(modulus)) as u64;
+        },
+    )
+}
+
+/// Computes the minimum costs like `costs`, and for each vertex the list of
+/// its predecessors on shortest paths, i.e., every `u` with an edge to `v` of
+/// cost `w` such that the cost of `u` plus `w` equals the cost of `v`.
+///
+/// `preds` holds the predecessor lists, accessed through `pred`, which must
+/// start out empty. Predecessors are listed in the order their costs became
+/// final. The result is a DAG as long as every edge has a positive cost, while
+/// a zero-cost cycle of shortest paths shows up as a cycle.
+///
+/// # Examples
+///
+/// ```
+/// # use dijkstra::shortest_path_dag;
+/// let adj = vec![vec![(1, 1), (2, 1)], vec![(3, 1)], vec![(3, 1)], vec![]];
+/// let (costs, preds) = shortest_path_dag(
+///     0,
+///     |v| adj[v].iter().copied(),
+///     vec![u32::MAX; 4],
+///     |costs: &mut Vec<u32>, v| &mut costs[v],
+///     vec![vec![]; 4],
+///     |preds: &mut Vec<Vec<usize>>, v| &mut preds[v],
+/// );
+/// assert_eq!(costs, vec![0, 1, 1, 2]);
+/// assert_eq!(preds, vec![vec![], vec![0], vec![0], vec![2, 1]]);
+/// ```
+pub fn shortest_path_dag<V, Es, Vs, Ws, WsI, W, Ps, PsI>(
+    start: V,
+    mut neighbors: Es,
+    costs: Ws,
+    mut cost: WsI,
+    mut preds: Ps,
+    mut pred: PsI,
+) -> (Ws, Ps)
+where
+    V: Copy + Ord,
+    Es: FnMut(V) -> Vs,
+    Vs: IntoIterator<Item = (V, W)>,
+    WsI: FnMut(&mut Ws, V) -> &mut W,
+    W: Copy + Ord + Add<Output = W> + Sum,
+    PsI: FnMut(&mut Ps, V) -> &mut Vec<V>,
+{
+    let mut settled = Vec::new();
+    let mut costs = costs_with_visitor(start, &mut neighbors, costs, &mut cost, |v, c| {
+        settled.push((v, c))
+    });
+    for (u, current_cost) in settled {
+        for (v, cost_delta) in neighbors(u) {
+            if current_cost + cost_delta == *cost(&mut costs, v) {
+                pred(&mut preds, v).push(u);
+            }
+        }
+    }
+    (costs, preds)
+}
+
+/// Computes the cost of the shortest walk from `start` to `goal` that is
+/// strictly more expensive than the shortest one, or `None` if there is no
+/// such walk. Walks may revisit vertices and edges.
+///
+/// `costs` holds the best and second-best costs of each vertex, accessed
+/// through `cost`, and every entry must start out larger than any walk cost,
+/// e.g. `(u32::MAX, u32::MAX)`.
+///
+/// # Examples
+///
+/// ```
+/// # use dijkstra::second_shortest;
+/// let adj = vec![vec![(1, 1), (2, 3)], vec![(2, 1)], vec![]];
+/// let second = second_shortest(
+///     0,
+///     2,
+///     |v| adj[v].iter().copied(),
+///     vec![(u32::MAX, u32::MAX); 3],
+///     |costs: &mut Vec<(u32, u32)>, v| &mut costs[v],
+/// );
+/// assert_eq!(second, Some(3));
+/// ```
+pub fn second_shortest<V, Es, Vs, Ws, WsI, W>(
+    start: V,
+    goal: V,
+    mut neighbors: Es,
+    mut costs: Ws,
+    mut cost: WsI,
+) -> Option<W>
+where
+    V: Copy + Ord,
+    Es: FnMut(V) -> Vs,
+    Vs: IntoIterator<Item = (V, W)>,
+    WsI: FnMut(&mut Ws, V) -> &mut (W, W),
+    W: Copy + Ord + Add<Output = W> + Sum,
+{
+    let unreached = cost(&mut costs, goal).1;
+    cost(&mut costs, start).0 = iter::empty().sum();
+    let queue = &mut BinaryHeap::from(vec![(Reverse(iter::empty().sum()), start)]);
+    while let Some((Reverse(current_cost), current_node)) = queue.pop() {
+        if cost(&mut costs, current_node).1 < current_cost {
+            continue;
+        }
+        for (next_node, cost_delta) in neighbors(current_node) {
+            let next_cost = current_cost + cost_delta;
+            let entry = cost(&mut costs, next_node);
+            if next_cost < entry.0 {
+                *entry = (next_cost, entry.0);
+            } else if entry.0 < next_cost && next_cost < entry.1 {
+                entry.1 = next_cost;
+            } else {
+                continue;
+            }
+            queue.push((Reverse(next_cost), next_node));
+        }
+    }
+    let second = cost(&mut costs, goal).1;
+    if second < unreached {
+        Some(second)
+    } else {
+        None
+    }
+}
+
+/// Computes the minimum cost to reach each cell of a grid from `start`, moving
+/// in four directions, where the value of a cell is the cost of entering it.
+/// Cells for which `passable` returns false are never entered. Unreachable
+/// cells have cost `u32::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// # use dijkstra::grid_costs;
+/// let grid = vec![vec![1, 1, 1], vec![1, 0, 1], vec![1, 5, 1]];
+/// let costs = grid_costs(&grid, (0, 0), |c| c > 0);
+/// assert_eq!(costs, vec![vec![0, 1, 2], vec![1, u32::MAX, 3], vec![2, 7, 4]]);
+/// ```
+pub fn grid_costs(
+    grid: &[Vec<u32>],
+    start: (usize, usize),
+    passable: fn(u32) -> bool,
+) -> Vec<Vec<u32>> {
+    costs(
+        start,
+        |(i, j): (usize, usize)| {
+            [
+                (i.wrapping_sub(1), j),
+                (i + 1, j),
+                (i, j.wrapping_sub(1)),
+                (i, j + 1),
+            ]
+            .into_iter()
+            .filter(|&(i, j)| i < grid.len() && j < grid[i].len() && passable(grid[i][j]))
+            .map(|(i, j)| ((i, j), grid[i][j]))
+        },
+        grid.iter().map(|row| vec![u32::MAX; row.len()]).collect(),
+        |costs: &mut Vec<Vec<u32>>, (i, j)| &mut costs[i][j],
+    )
+}
+
+/// Computes the number of edges on a shortest path from the nearest of
+/// `sources` to each vertex of an unweighted graph with a breadth-first
+/// search. Unreachable vertices have distance `usize::MAX`.
+///
+/// # Examples
+///
+/// ```
+/// # use dijkstra::bfs_layers;
+/// let adj = vec![vec![1], vec![0, 2], vec![1, 3], vec![2], vec![]];
+/// assert_eq!(bfs_layers(&adj, &[0, 3]), vec![0, 1, 1, 0, usize::MAX]);
+/// ```
+pub fn bfs_layers(adj: &[Vec<usize>], sources: &[usize]) -> Vec<usize> {
+    let mut distances = vec![usize::MAX; adj.len()];
+    let mut queue = VecDeque::new();
+    for &source in sources {
+        if distances[source] == usize::MAX {
+            distances[source] = 0;
+            queue.push_back(source);
+        }
+    }
+    while let Some(u) = queue.pop_front() {
+        for &v in &adj[u] {
+            if distances[v] == usize::MAX {
+                distances[v] = distances[u] + 1;
+                queue.push_back(v);
+            }
+        }
+    }
+    distances
+}
+
+/// Computes the minimum cost to reach each vertex from `start` in a graph that
+/// may have negative edges, using the queue-based Bellman-Ford algorithm
+/// (SPFA). `adj[u]` lists `(v, w)` for each edge from `u` to `v` of cost `w`.
+/// Unreachable vertices have cost `i64::MAX`.
+///
+/// Returns `None` if a vertex is relaxed more than `n` times, which happens
+/// only if a negative cycle is reachable from `start`. Runs in O(NM) in the
+/// worst case, but is usually much faster on sparse graphs.
+///
+/// # Examples
+///
+/// ```
+/// # use dijkstra::spfa;
+/// let adj = vec![vec![(1, 4), (2, 1)], vec![], vec![(1, -2)]];
+/// assert_eq!(spfa(3, &adj, 0), Some(vec![0, -1, 1]));
+/// ```
+pub fn spfa(n: usize, adj: &[Vec<(usize, i64)>], start: usize) -> Option<Vec<i64>> {
+    let mut costs = vec![i64::MAX; n];
+    let mut relaxed = vec![0; n];
+    let mut in_queue = vec![false; n];
+    let mut queue = VecDeque::from(vec![start]);
+    costs[start] = 0;
+    in_queue[start] = true;
+    while let Some(u) = queue.pop_front() {
+        in_queue[u] = false;
+        for &(v, w) in &adj[u] {
+            let next_cost = costs[u] + w;
+            if next_cost < costs[v] {
+                costs[v] = next_cost;
+                relaxed[v] += 1;
+                if relaxed[v] > n {
+                    return None;
+                }
+                if !in_queue[v] {
+                    in_queue[v] = true;
+                    queue.push_back(v);
+                }
+            }
+        }
+    }
+    Some(costs)
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn grid_costs() {
+        let wall = u32::MAX;
+        let grid = vec![
+            vec![1, 1, 1, 1],
+            vec![1, wall, wall, 1],
+            vec![1, 9, wall, 1],
+            vec![1, 1, 1, 1],
+        ];
+        let costs = super::grid_costs(&grid, (2, 1), |c| c != u32::MAX);
+        assert_eq!(
+            costs,
+            vec![
+                vec![3, 4, 5, 6],
+                vec![2, u32::MAX, u32::MAX, 5],
+                vec![1, 0, u32::MAX, 4],
+                vec![2, 1, 2, 3],
+            ]
+        );
+    }
+
+    #[test]
+    fn costs_expands_once() {
+        let mut state = 88172645463325252u64;
+        let mut next = |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+        // A dense graph pushes many stale entries for each vertex
+        let n = 60;
+        let adj = (0..n)
+            .map(|_| {
+                (0..n)
+                    .map(|v| (v, next(200) as u32))
+                    .filter(|&(_, c)| c < 100)
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let mut expanded = vec![0; n];
+        let costs = super::costs(
+            0,
+            |v| {
+                expanded[v] += 1;
+                adj[v].iter().copied()
+            },
+            vec![u32::MAX; n],
+            |costs: &mut Vec<u32>, v| &mut costs[v],
+        );
+        for v in 0..n {
+            assert_eq!(expanded[v], usize::from(costs[v] != u32::MAX));
+        }
+    }
+
+    #[test]
+    fn costs_with_visitor() {
+        let adj = [
+            vec![(1, 4), (2, 1)],
+            vec![(3, 1)],
+            vec![(1, 2), (3, 5)],
+            vec![],
+            vec![(0, 1)],
+        ];
+        let mut settled = Vec::new();
+        let costs = super::costs_with_visitor(
+            0,
+            |v| adj[v].iter().copied(),
+            vec![u32::MAX; 5],
+            |costs: &mut Vec<u32>, v| &mut costs[v],
+            |v, c| settled.push((v, c)),
+        );
+        assert_eq!(costs, vec![0, 3, 1, 4, u32::MAX]);
+        assert_eq!(settled, vec![(0, 0), (2, 1), (1, 3), (3, 4)]);
+    }
+
+    #[test]
+    fn costs_and_edge_counts() {
+        // 0 -> 1 -> 2 -> 3 costs 3 with 3 edges, 0 -> 4 -> 3 costs 3 with 2
+        // edges, and the zero-cost edge 1 -> 4 gives 4 a second path
+        let adj = [
+            vec![(1, 1), (4, 1)],
+            vec![(2, 1), (4, 0)],
+            vec![(3, 1)],
+            vec![(5, 0)],
+            vec![(3, 2)],
+            vec![],
+        ];
+        let (costs, edge_counts) = super::costs_and_edge_counts(
+            0,
+            |v| adj[v].iter().copied(),
+            vec![u32::MAX; 6],
+            |costs: &mut Vec<u32>, v| &mut costs[v],
+            vec![usize::MAX; 6],
+            |edge_counts: &mut Vec<usize>, v| &mut edge_counts[v],
+        );
+        assert_eq!(costs, vec![0, 1, 2, 3, 1, 3]);
+        assert_eq!(edge_counts, vec![0, 1, 2, 2, 1, 3]);
+    }
+
+    #[test]
+    fn costs_and_counts_mod() {
+        // A chain of 100 diamonds has 2^100 shortest paths from end to end
+        const MOD: u64 = 1_000_000_007;
+        let n = 3 * 100 + 1;
+        let neighbors = |v: usize| match v % 3 {
+            0 if v + 1 < n => vec![(v + 1, 1), (v + 2, 1)],
+            1 | 2 => vec![(v + 3 - v % 3, 1)],
+            _ => vec![],
+        };
+        let (costs, counts) = super::costs_and_counts_mod(
+            0,
+            neighbors,
+            vec![u32::MAX; n],
+            |costs: &mut Vec<u32>, v| &mut costs[v],
+            vec![0; n],
+            |counts: &mut Vec<u64>, v| &mut counts[v],
+            MOD,
+        );
+        assert_eq!(costs[n - 1], 200);
+        let expected = (0..100).fold(1, |acc, _| acc * 2 % MOD);
+        assert_eq!(counts[n - 1], expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "the modulus must be positive")]
+    fn costs_and_counts_mod_zero() {
+        super::costs_and_counts_mod(
+            0,
+            |_| vec![],
+            vec![u32::MAX; 1],
+            |costs: &mut Vec<u32>, v| &mut costs[v],
+            vec![0; 1],
+            |counts: &mut Vec<u64>, v| &mut counts[v],
+            0,
+        );
+    }
+
+    #[test]
+    fn shortest_path_dag() {
+        //     1
+        //   /   \
+        // 0 - 2 - 4 - 5
+        //   \   /
+        //     3
+        // Every path from 0 to 4 costs 4, while 0 -> 2 -> 3 and 3 -> 5 are
+        // longer than necessary, and 6 is unreachable
+        let edges = [
+            (0, 1, 1),
+            (1, 4, 3),
+            (0, 2, 2),
+            (2, 4, 2),
+            (0, 3, 3),
+            (3, 4, 1),
+            (2, 3, 2),
+            (4, 5, 1),
+            (3, 5, 3),
+            (6, 0, 1),
+        ];
+        let mut adj = vec![vec![]; 7];
+        for (u, v, w) in edges {
+            adj[u].push((v, w));
+        }
+        let (costs, mut preds) = super::shortest_path_dag(
+            0,
+            |v| adj[v].iter().copied(),
+            vec![u32::MAX; 7],
+            |costs: &mut Vec<u32>, v| &mut costs[v],
+            vec![vec![]; 7],
+            |preds: &mut Vec<Vec<usize>>, v| &mut preds[v],
+        );
+        assert_eq!(costs, vec![0, 1, 2, 3, 4, 5, u32::MAX]);
+        for p in &mut preds {
+            p.sort_unstable();
+        }
+        assert_eq!(
+            preds,
+            vec![
+                vec![],
+                vec![0],
+                vec![0],
+                vec![0],
+                vec![1, 2, 3],
+                vec![4],
+                vec![]
+            ]
+        );
+
+        // Every edge on the DAG satisfies the shortest-path equation, and every
+        // edge satisfying it is on the DAG
+        for (u, v, w) in edges {
+            let on_dag = costs[u] != u32::MAX && costs[u] + w == costs[v];
+            assert_eq!(preds[v].contains(&u), on_dag);
+        }
+    }
+
+    #[test]
+    fn second_shortest() {
+        let run = |adj: &[Vec<(usize, u32)>], start: usize, goal: usize| {
+            super::second_shortest(
+                start,
+                goal,
+                |v| adj[v].iter().copied(),
+                vec![(u32::MAX, u32::MAX); adj.len()],
+                |costs: &mut Vec<(u32, u32)>, v| &mut costs[v],
+            )
+        };
+
+        // The shortest path 0 -> 1 -> 3 -> 4 costs 4, and the detour through 2
+        // costs 5, far less than twice the shortest
+        let mut adj = vec![vec![]; 5];
+        for (u, v, w) in [(0, 1, 1), (1, 3, 2), (3, 4, 1), (1, 2, 1), (2, 3, 2)] {
+            adj[u].push((v, w));
+            adj[v].push((u, w));
+        }
+        assert_eq!(run(&adj, 0, 4), Some(5));
+        // Another shortest path of the same cost does not count as the second
+        adj[0].push((3, 3));
+        adj[3].push((0, 3));
+        assert_eq!(run(&adj, 0, 4), Some(5));
+        // Going back and forth along the only edge
+        assert_eq!(run(&[vec![(1, 3)], vec![(0, 3)]], 0, 1), Some(9));
+        assert_eq!(run(&[vec![(1, 3)], vec![(0, 3)]], 0, 0), Some(6));
+
+        // A directed acyclic graph with a single path has no second path
+        assert_eq!(run(&[vec![(1, 1)], vec![(2, 1)], vec![]], 0, 2), None);
+        assert_eq!(run(&[vec![], vec![]], 0, 1), None);
+    }
+
+    #[test]
+    fn bfs_layers() {
+        // A 3 x 4 grid with a wall at (1, 1) and (1, 2), plus an isolated node
+        let (h, w) = (3, 4);
+        let wall = |i: usize, j: usize| i == 1 && (j == 1 || j == 2);
+        let mut adj = vec![vec![]; h * w + 1];
+        for i in 0..h {
+            for j in 0..w {
+                if wall(i, j) {
+                    continue;
+                }
+                if i + 1 < h && !wall(i + 1, j) {
+                    adj[i * w + j].push((i + 1) * w + j);
+                    adj[(i + 1) * w + j].push(i * w + j);
+                }
+                if j + 1 < w && !wall(i, j + 1) {
+                    adj[i * w + j].push(i * w + j + 1);
+                    adj[i * w + j + 1].push(i * w + j);
+                }
+            }
+        }
+        let distances = super::bfs_layers(&adj, &[0, 2 * w + 2]);
+        let m = usize::MAX;
+        assert_eq!(distances, vec![0, 1, 2, 3, 1, m, m, 2, 2, 1, 0, 1, m]);
+    }
+
+    #[test]
+    fn spfa() {
+        let adj = vec![
+            vec![(1, 6), (2, 7)],
+            vec![(3, 5), (2, 8), (4, -4)],
+            vec![(3, -3), (4, 9)],
+            vec![(1, -2)],
+            vec![(0, 2), (3, 7)],
+            vec![(0, 1)],
+        ];
+        assert_eq!(
+            super::spfa(6, &adj, 0),
+            Some(vec![0, 2, 7, 4, -2, i64::MAX])
+        );
+
+        let adj = vec![vec![(1, 1)], vec![(2, -2)], vec![(1, 1), (3, 1)], vec![]];
+        assert_eq!(super::spfa(4, &adj, 0), None);
+        assert_eq!(
+            super::spfa(4, &adj, 3),
+            Some(vec![i64::MAX, i64::MAX, i64::MAX, 0])
+        );
+    }
+}
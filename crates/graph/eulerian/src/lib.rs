@@ -1,3 +1,5 @@
+use acl_dsu::Dsu;
+
 /// Finds an Eulerian circuit in an undirected graph represented by an adjacency
 /// matrix.
 ///
@@ -93,3 +95,330 @@ pub fn find_eulerian_circuit(adj_matrix: &[Vec<u32>]) -> Option<Vec<usize>> {
         None
     }
 }
+
+/// Finds an Eulerian circuit in an undirected multigraph with `n` vertices
+/// given by its edges, and returns the indices of the edges in the order they
+/// are traversed. Parallel edges and loops are distinct edges, each used
+/// exactly once.
+///
+/// Returns `None` if some vertex has odd degree or the edges do not all
+/// belong to a single connected component.
+///
+/// # Examples
+///
+/// ```
+/// # use eulerian::find_eulerian_circuit_multigraph;
+/// let edges = [(0, 1), (1, 2), (2, 0)];
+/// let circuit = find_eulerian_circuit_multigraph(3, &edges).unwrap();
+/// assert_eq!(circuit.len(), 3);
+/// ```
+pub fn find_eulerian_circuit_multigraph(n: usize, edges: &[(usize, usize)]) -> Option<Vec<usize>> {
+    let mut adj = vec![vec![]; n];
+    for (i, &(u, v)) in edges.iter().enumerate() {
+        adj[u].push((v, i));
+        adj[v].push((u, i));
+    }
+    if adj.iter().any(|incident| incident.len() % 2 != 0) {
+        return None;
+    }
+    let start = match edges.first() {
+        Some(&(u, _)) => u,
+        None => return Some(Vec::new()),
+    };
+
+    let mut used = vec![false; edges.len()];
+    let mut next = vec![0; n];
+    // Each entry holds a vertex and the edge used to reach it
+    let mut stack = vec![(start, usize::MAX)];
+    let mut circuit = Vec::with_capacity(edges.len());
+    while let Some(&(v, edge)) = stack.last() {
+        while next[v] < adj[v].len() && used[adj[v][next[v]].1] {
+            next[v] += 1;
+        }
+        if let Some(&(u, i)) = adj[v].get(next[v]) {
+            used[i] = true;
+            stack.push((u, i));
+        } else {
+            stack.pop();
+            if edge != usize::MAX {
+                circuit.push(edge);
+            }
+        }
+    }
+
+    // Edges left unused belong to another component
+    if circuit.len() == edges.len() {
+        circuit.reverse();
+        Some(circuit)
+    } else {
+        None
+    }
+}
+
+/// Finds an Eulerian circuit in a directed graph represented by an adjacency
+/// matrix, where `adj_matrix[u][v]` is the number of edges from `u` to `v`.
+///
+/// Returns `None` if some vertex has different in- and out-degrees, or if the
+/// edges do not all belong to a single weakly connected component.
+///
+/// # Examples
+///
+/// ```
+/// # use eulerian::find_directed_eulerian_circuit;
+/// let adj_matrix = vec![
+///     vec![0, 1, 0],
+///     vec![0, 0, 1],
+///     vec![1, 0, 0],
+/// ];
+/// assert_eq!(find_directed_eulerian_circuit(&adj_matrix), Some(vec![0, 1, 2, 0]));
+/// ```
+pub fn find_directed_eulerian_circuit(adj_matrix: &[Vec<u32>]) -> Option<Vec<usize>> {
+    let (out_degrees, in_degrees) = directed_degrees(adj_matrix);
+    if out_degrees != in_degrees {
+        return None;
+    }
+    let start = out_degrees.iter().position(|&d| d > 0).unwrap_or(0);
+    directed_eulerian_walk(adj_matrix, start)
+}
+
+/// Finds an Eulerian path in a directed graph represented by an adjacency
+/// matrix, where `adj_matrix[u][v]` is the number of edges from `u` to `v`.
+///
+/// The path is a circuit if every vertex has equal in- and out-degrees.
+/// Returns `None` if the degrees do not allow an Eulerian path, or if the
+/// edges do not all belong to a single weakly connected component.
+///
+/// # Examples
+///
+/// ```
+/// # use eulerian::find_directed_eulerian_path;
+/// let adj_matrix = vec![
+///     vec![0, 1, 0],
+///     vec![0, 0, 1],
+///     vec![0, 0, 0],
+/// ];
+/// assert_eq!(find_directed_eulerian_path(&adj_matrix), Some(vec![0, 1, 2]));
+/// ```
+pub fn find_directed_eulerian_path(adj_matrix: &[Vec<u32>]) -> Option<Vec<usize>> {
+    let (out_degrees, in_degrees) = directed_degrees(adj_matrix);
+    let mut start = None;
+    let mut end = None;
+    for (v, (&out_degree, &in_degree)) in out_degrees.iter().zip(&in_degrees).enumerate() {
+        if out_degree == in_degree + 1 && start.is_none() {
+            start = Some(v);
+        } else if in_degree == out_degree + 1 && end.is_none() {
+            end = Some(v);
+        } else if in_degree != out_degree {
+            return None;
+        }
+    }
+    let start = match (start, end) {
+        (Some(start), Some(_)) => start,
+        (None, None) => out_degrees.iter().position(|&d| d > 0).unwrap_or(0),
+        _ => return None,
+    };
+    directed_eulerian_walk(adj_matrix, start)
+}
+
+/// Counts the connected components of the graph represented by an adjacency
+/// matrix that contain at least one edge, ignoring isolated vertices. An
+/// Eulerian circuit or path can only exist if this is at most one.
+///
+/// Edges are treated as undirected, so for a directed graph this counts the
+/// weakly connected components.
+///
+/// # Examples
+///
+/// ```
+/// # use eulerian::edge_components;
+/// let adj_matrix = vec![
+///     vec![0, 1, 0, 0],
+///     vec![1, 0, 0, 0],
+///     vec![0, 0, 0, 0],
+///     vec![0, 0, 0, 2],
+/// ];
+/// assert_eq!(edge_components(&adj_matrix), 2);
+/// ```
+pub fn edge_components(adj_matrix: &[Vec<u32>]) -> usize {
+    let n = adj_matrix.len();
+    let mut dsu = Dsu::new(n);
+    let mut has_edge = vec![false; n];
+    for (u, row) in adj_matrix.iter().enumerate() {
+        for (v, &count) in row.iter().enumerate() {
+            if count > 0 {
+                dsu.merge(u, v);
+                has_edge[u] = true;
+                has_edge[v] = true;
+            }
+        }
+    }
+    (0..n)
+        .filter(|&v| has_edge[v] && dsu.leader(v) == v)
+        .count()
+}
+
+fn directed_degrees(adj_matrix: &[Vec<u32>]) -> (Vec<u32>, Vec<u32>) {
+    let n = adj_matrix.len();
+    let mut out_degrees = vec![0; n];
+    let mut in_degrees = vec![0; n];
+    for (u, row) in adj_matrix.iter().enumerate() {
+        for (v, &count) in row.iter().enumerate() {
+            out_degrees[u] += count;
+            in_degrees[v] += count;
+        }
+    }
+    (out_degrees, in_degrees)
+}
+
+/// Runs Hierholzer's algorithm from `start` once every vertex with an edge is
+/// known to be weakly connected to it.
+fn directed_eulerian_walk(adj_matrix: &[Vec<u32>], start: usize) -> Option<Vec<usize>> {
+    let n = adj_matrix.len();
+    if n == 0 {
+        return Some(Vec::new());
+    }
+
+    // Check that the edges lie in a single weakly connected component
+    if edge_components(adj_matrix) > 1 {
+        return None;
+    }
+
+    let mut remaining = adj_matrix.to_vec();
+    let mut next = vec![0; n];
+    let mut stack = vec![start];
+    let mut walk = Vec::new();
+    while let Some(&v) = stack.last() {
+        while next[v] < n && remaining[v][next[v]] == 0 {
+            next[v] += 1;
+        }
+        if next[v] < n {
+            let u = next[v];
+            remaining[v][u] -= 1;
+            stack.push(u);
+        } else {
+            stack.pop();
+            walk.push(v);
+        }
+    }
+    walk.reverse();
+    Some(walk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        edge_components, find_directed_eulerian_circuit, find_directed_eulerian_path,
+        find_eulerian_circuit_multigraph,
+    };
+
+    /// Checks that `circuit` uses every edge once and forms a closed walk.
+    fn assert_circuit(edges: &[(usize, usize)], circuit: &[usize]) {
+        let mut sorted = circuit.to_vec();
+        sorted.sort();
+        assert_eq!(sorted, (0..edges.len()).collect::<Vec<_>>());
+
+        let (u, v) = edges[circuit[0]];
+        let last = edges[*circuit.last().unwrap()];
+        let start = if last.0 == u || last.1 == u { u } else { v };
+        let mut current = start;
+        for &i in circuit {
+            let (u, v) = edges[i];
+            current = if current == u {
+                v
+            } else {
+                assert_eq!(current, v);
+                u
+            };
+        }
+        assert_eq!(current, start);
+    }
+
+    #[test]
+    fn multigraph_circuit() {
+        // Three parallel edges between 0 and 1 closed by a path through 2
+        let edges = [(0, 1), (1, 0), (0, 1), (1, 2), (2, 0)];
+        let circuit = find_eulerian_circuit_multigraph(3, &edges).unwrap();
+        assert_circuit(&edges, &circuit);
+
+        // Without the path, 0 and 1 have odd degrees
+        let edges = [(0, 1), (1, 0), (0, 1), (1, 2), (2, 3), (3, 1)];
+        assert_eq!(find_eulerian_circuit_multigraph(4, &edges), None);
+
+        let edges = [
+            (0, 1),
+            (1, 0),
+            (0, 1),
+            (1, 0),
+            (1, 2),
+            (2, 3),
+            (3, 1),
+            (3, 3),
+        ];
+        let circuit = find_eulerian_circuit_multigraph(4, &edges).unwrap();
+        assert_circuit(&edges, &circuit);
+
+        let edges = [(0, 1), (1, 0), (2, 3), (3, 2)];
+        assert_eq!(find_eulerian_circuit_multigraph(4, &edges), None);
+        assert_eq!(find_eulerian_circuit_multigraph(2, &[]), Some(vec![]));
+    }
+
+    #[test]
+    fn directed_circuit() {
+        let adj_matrix = vec![
+            vec![0, 1, 0, 1],
+            vec![0, 0, 1, 0],
+            vec![1, 0, 0, 0],
+            vec![1, 0, 0, 0],
+        ];
+        let circuit = find_directed_eulerian_circuit(&adj_matrix).unwrap();
+        assert_eq!(circuit.len(), 6);
+        assert_eq!(circuit.first(), circuit.last());
+        let mut remaining = adj_matrix.clone();
+        for w in circuit.windows(2) {
+            assert!(remaining[w[0]][w[1]] > 0);
+            remaining[w[0]][w[1]] -= 1;
+        }
+
+        let adj_matrix = vec![vec![0, 1], vec![0, 0]];
+        assert_eq!(find_directed_eulerian_circuit(&adj_matrix), None);
+        assert_eq!(find_directed_eulerian_path(&adj_matrix), Some(vec![0, 1]));
+    }
+
+    #[test]
+    fn directed_disconnected() {
+        // Two disjoint cycles 0 -> 1 -> 0 and 2 -> 3 -> 2
+        let adj_matrix = vec![
+            vec![0, 1, 0, 0],
+            vec![1, 0, 0, 0],
+            vec![0, 0, 0, 1],
+            vec![0, 0, 1, 0],
+        ];
+        assert_eq!(find_directed_eulerian_circuit(&adj_matrix), None);
+        assert_eq!(find_directed_eulerian_path(&adj_matrix), None);
+
+        // Isolated vertices do not matter
+        let adj_matrix = vec![vec![0, 0, 0], vec![0, 0, 1], vec![0, 1, 0]];
+        assert_eq!(
+            find_directed_eulerian_circuit(&adj_matrix),
+            Some(vec![1, 2, 1])
+        );
+    }
+
+    #[test]
+    fn edge_components_with_isolated_vertices() {
+        // A triangle on 1, 3, 5 and a loop on 4, with 0, 2 and 6 isolated
+        let mut adj_matrix = vec![vec![0; 7]; 7];
+        for (u, v) in [(1, 3), (3, 5), (5, 1)] {
+            adj_matrix[u][v] = 1;
+            adj_matrix[v][u] = 1;
+        }
+        adj_matrix[4][4] = 2;
+        assert_eq!(edge_components(&adj_matrix), 2);
+        assert!(super::find_eulerian_circuit(&adj_matrix).is_none());
+
+        adj_matrix[4][4] = 0;
+        assert_eq!(edge_components(&adj_matrix), 1);
+        assert_eq!(edge_components(&[vec![0; 3], vec![0; 3], vec![0; 3]]), 0);
+        assert_eq!(edge_components(&[]), 0);
+    }
+}
@@ -0,0 +1,432 @@
+/// Builds the adjacency list of a graph with `n` vertices from its edges. Each
+/// undirected edge is added in both directions.
+///
+/// # Examples
+///
+/// ```
+/// # use graph::adjacency_from_edges;
+/// let adj = adjacency_from_edges(3, &[(0, 1), (1, 2)], false);
+/// assert_eq!(adj, vec![vec![1], vec![0, 2], vec![1]]);
+/// ```
+pub fn adjacency_from_edges(n: usize, edges: &[(usize, usize)], directed: bool) -> Vec<Vec<usize>> {
+    let mut adj = vec![vec![]; n];
+    for &(u, v) in edges {
+        adj[u].push(v);
+        if !directed {
+            adj[v].push(u);
+        }
+    }
+    adj
+}
+
+/// Checks if an undirected graph, given with each edge in both directions, is
+/// a tree, that is, it is connected and has exactly `n - 1` edges. A graph
+/// without vertices is not a tree.
+///
+/// # Examples
+///
+/// ```
+/// # use graph::{adjacency_from_edges, is_tree};
+/// assert!(is_tree(&adjacency_from_edges(3, &[(0, 1), (1, 2)], false)));
+/// assert!(!is_tree(&adjacency_from_edges(3, &[(0, 1), (1, 0)], false)));
+/// ```
+pub fn is_tree(adj: &[Vec<usize>]) -> bool {
+    let n = adj.len();
+    let degree_sum = adj.iter().map(Vec::len).sum::<usize>();
+    if n == 0 || degree_sum != 2 * (n - 1) {
+        return false;
+    }
+
+    let mut visited = vec![false; n];
+    visited[0] = true;
+    let mut stack = vec![0];
+    let mut count = 1;
+    while let Some(v) = stack.pop() {
+        for &u in &adj[v] {
+            if !visited[u] {
+                visited[u] = true;
+                count += 1;
+                stack.push(u);
+            }
+        }
+    }
+    count == n
+}
+
+/// Traverses a tree in preorder from `root` and returns `(tin, tout, order)`,
+/// where `order` lists the vertices in visiting order and the subtree of `v`
+/// is `order[tin[v]..tout[v]]`. Vertices not reachable from `root` are left
+/// with `tin[v] == tout[v] == 0`.
+///
+/// # Examples
+///
+/// ```
+/// # use graph::{adjacency_from_edges, euler_tour};
+/// let adj = adjacency_from_edges(4, &[(0, 1), (1, 2), (0, 3)], false);
+/// let (tin, tout, order) = euler_tour(&adj, 0);
+/// assert_eq!(order, vec![0, 1, 2, 3]);
+/// assert_eq!(tin, vec![0, 1, 2, 3]);
+/// assert_eq!(tout, vec![4, 3, 3, 4]);
+/// ```
+pub fn euler_tour(adj: &[Vec<usize>], root: usize) -> (Vec<usize>, Vec<usize>, Vec<usize>) {
+    let n = adj.len();
+    let mut tin = vec![0; n];
+    let mut tout = vec![0; n];
+    let mut order = Vec::with_capacity(n);
+
+    // Each entry holds a vertex, its parent, and the index of its next child
+    let mut stack = vec![(root, usize::MAX, 0)];
+    tin[root] = 0;
+    order.push(root);
+    while let Some((v, parent, i)) = stack.last_mut() {
+        if let Some(&u) = adj[*v].get(*i) {
+            *i += 1;
+            if u != *parent {
+                let v = *v;
+                tin[u] = order.len();
+                order.push(u);
+                stack.push((u, v, 0));
+            }
+        } else {
+            tout[*v] = order.len();
+            stack.pop();
+        }
+    }
+    (tin, tout, order)
+}
+
+/// Builds the centroid decomposition of a forest and returns the parent of
+/// each vertex in the centroid tree, or `usize::MAX` for the centroids of
+/// whole components. Every vertex has O(log N) ancestors in the centroid tree.
+///
+/// # Examples
+///
+/// ```
+/// # use graph::{adjacency_from_edges, centroid_decomposition};
+/// let adj = adjacency_from_edges(5, &[(0, 1), (1, 2), (2, 3), (3, 4)], false);
+/// assert_eq!(centroid_decomposition(&adj), vec![1, 2, usize::MAX, 2, 3]);
+/// ```
+pub fn centroid_decomposition(adj: &[Vec<usize>]) -> Vec<usize> {
+    let n = adj.len();
+    let mut parent = vec![usize::MAX; n];
+    let mut removed = vec![false; n];
+    let mut size = vec![0; n];
+    let mut tree_parent = vec![usize::MAX; n];
+    let mut order = Vec::with_capacity(n);
+
+    // Each entry holds a vertex of an undecomposed component and the centroid
+    // that split it off
+    let mut components = (0..n).rev().map(|v| (v, usize::MAX)).collect::<Vec<_>>();
+    while let Some((root, centroid_parent)) = components.pop() {
+        if removed[root] {
+            continue;
+        }
+
+        // Visit the component in BFS order, then accumulate sizes backwards
+        order.clear();
+        order.push(root);
+        tree_parent[root] = usize::MAX;
+        let mut i = 0;
+        while i < order.len() {
+            let v = order[i];
+            i += 1;
+            for &u in &adj[v] {
+                if u != tree_parent[v] && !removed[u] {
+                    tree_parent[u] = v;
+                    order.push(u);
+                }
+            }
+        }
+        for &v in order.iter().rev() {
+            size[v] = 1 + adj[v]
+                .iter()
+                .filter(|&&u| u != tree_parent[v] && !removed[u])
+                .map(|&u| size[u])
+                .sum::<usize>();
+        }
+
+        // Walk towards the subtree that holds more than half of the vertices
+        let total = order.len();
+        let mut centroid = root;
+        while let Some(&u) = adj[centroid]
+            .iter()
+            .find(|&&u| u != tree_parent[centroid] && !removed[u] && 2 * size[u] > total)
+        {
+            centroid = u;
+        }
+
+        parent[centroid] = centroid_parent;
+        removed[centroid] = true;
+        components.extend(
+            adj[centroid]
+                .iter()
+                .filter(|&&u| !removed[u])
+                .map(|&u| (u, centroid)),
+        );
+    }
+    parent
+}
+
+/// Computes an aggregate of the whole tree as seen from every possible root
+/// with the rerooting technique, calling each closure O(N) times in total.
+/// Each tree of a forest is handled separately.
+///
+/// The aggregate of the subtree rooted at `v` is built from those of its
+/// children with three closures:
+///
+/// - `add_edge(x, u, v)` extends the aggregate `x` of the subtree rooted at
+///   `u` along the edge from `u` to its parent `v`.
+/// - `merge(x, y)` combines the extended aggregates of two children. It has to
+///   be associative and commutative, with `identity` as its identity element.
+/// - `finalize(x, v)` adds `v` itself to the merged aggregate `x` of all of its
+///   children.
+///
+/// # Examples
+///
+/// Computes the number of vertices in the largest subtree hanging off each
+/// vertex.
+///
+/// ```
+/// # use graph::{adjacency_from_edges, reroot};
+/// let adj = adjacency_from_edges(5, &[(0, 1), (1, 2), (1, 3), (3, 4)], false);
+/// let sizes = reroot(
+///     &adj,
+///     (0, 0),
+///     |x, y| (x.0 + y.0, x.1.max(y.1)),
+///     |x, _, _| (x.0, x.0),
+///     |x, _| (x.0 + 1, x.1),
+/// );
+/// let largest = sizes.iter().map(|x| x.1).collect::<Vec<_>>();
+/// assert_eq!(largest, vec![4, 2, 4, 3, 4]);
+/// ```
+pub fn reroot<T, M, E, F>(
+    adj: &[Vec<usize>],
+    identity: T,
+    merge: M,
+    add_edge: E,
+    finalize: F,
+) -> Vec<T>
+where
+    T: Clone,
+    M: Fn(&T, &T) -> T,
+    E: Fn(&T, usize, usize) -> T,
+    F: Fn(&T, usize) -> T,
+{
+    let n = adj.len();
+    let mut parent = vec![usize::MAX; n];
+    let mut visited = vec![false; n];
+    let mut order = Vec::with_capacity(n);
+    for root in 0..n {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        let start = order.len();
+        order.push(root);
+        let mut i = start;
+        while i < order.len() {
+            let v = order[i];
+            i += 1;
+            for &u in &adj[v] {
+                if !visited[u] {
+                    visited[u] = true;
+                    parent[u] = v;
+                    order.push(u);
+                }
+            }
+        }
+    }
+
+    // The aggregate of the subtree rooted at each vertex
+    let mut down = vec![identity.clone(); n];
+    for &v in order.iter().rev() {
+        let mut acc = identity.clone();
+        for &u in &adj[v] {
+            if u != parent[v] {
+                acc = merge(&acc, &add_edge(&down[u], u, v));
+            }
+        }
+        down[v] = finalize(&acc, v);
+    }
+
+    // The aggregate of everything outside the subtree rooted at each vertex,
+    // as a subtree rooted at its parent
+    let mut up = vec![identity.clone(); n];
+    let mut result = vec![identity.clone(); n];
+    let mut values = Vec::new();
+    let mut suffix = Vec::new();
+    for &v in &order {
+        values.clear();
+        values.extend(adj[v].iter().map(|&u| {
+            if u == parent[v] {
+                add_edge(&up[v], u, v)
+            } else {
+                add_edge(&down[u], u, v)
+            }
+        }));
+        suffix.clear();
+        suffix.push(identity.clone());
+        for value in values.iter().rev() {
+            suffix.push(merge(value, suffix.last().unwrap()));
+        }
+        suffix.reverse();
+
+        result[v] = finalize(&suffix[0], v);
+        let mut prefix = identity.clone();
+        for (i, &u) in adj[v].iter().enumerate() {
+            if u != parent[v] {
+                up[u] = finalize(&merge(&prefix, &suffix[i + 1]), v);
+            }
+            prefix = merge(&prefix, &values[i]);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn adjacency_from_edges() {
+        let edges = [(0, 1), (0, 2), (0, 3), (2, 3), (3, 4)];
+
+        let adj = super::adjacency_from_edges(5, &edges, false);
+        let degrees = adj.iter().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(degrees, vec![3, 1, 2, 3, 1]);
+        assert_eq!(adj[3], vec![0, 2, 4]);
+
+        let adj = super::adjacency_from_edges(5, &edges, true);
+        let degrees = adj.iter().map(Vec::len).collect::<Vec<_>>();
+        assert_eq!(degrees, vec![3, 0, 1, 1, 0]);
+        assert_eq!(adj[0], vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn euler_tour() {
+        //     0
+        //    / \
+        //   1   2
+        //  / \   \
+        // 3   4   5
+        //     |
+        //     6
+        let edges = [(0, 1), (0, 2), (1, 3), (1, 4), (2, 5), (4, 6)];
+        let adj = super::adjacency_from_edges(7, &edges, false);
+        let (tin, tout, order) = super::euler_tour(&adj, 0);
+        for v in 0..7 {
+            assert_eq!(order[tin[v]], v);
+            for &(p, c) in &edges {
+                if c == v {
+                    assert!(tin[p] < tin[v] && tout[v] <= tout[p]);
+                }
+            }
+        }
+        let mut subtree = order[tin[1]..tout[1]].to_vec();
+        subtree.sort();
+        assert_eq!(subtree, vec![1, 3, 4, 6]);
+        assert_eq!(tout[0] - tin[0], 7);
+        assert_eq!(tout[6] - tin[6], 1);
+
+        // A path deep enough to overflow a recursive traversal
+        let n = 1_000_000;
+        let edges = (1..n).map(|v| (v - 1, v)).collect::<Vec<_>>();
+        let adj = super::adjacency_from_edges(n, &edges, false);
+        let (tin, tout, _) = super::euler_tour(&adj, 0);
+        assert_eq!((tin[n - 1], tout[0]), (n - 1, n));
+    }
+
+    #[test]
+    fn centroid_decomposition() {
+        let depth = |parent: &[usize], mut v: usize| {
+            let mut depth = 0;
+            while parent[v] != usize::MAX {
+                v = parent[v];
+                depth += 1;
+            }
+            depth
+        };
+
+        let n = 1000;
+        let edges = (1..n).map(|v| (v - 1, v)).collect::<Vec<_>>();
+        let adj = super::adjacency_from_edges(n, &edges, false);
+        let parent = super::centroid_decomposition(&adj);
+        assert_eq!(parent.iter().filter(|&&p| p == usize::MAX).count(), 1);
+        assert!((0..n).all(|v| depth(&parent, v) < 10));
+
+        // Each centroid splits its component into pieces of at most half size
+        let edges = [(0, 1), (0, 2), (0, 3), (3, 4), (4, 5), (4, 6), (7, 8)];
+        let adj = super::adjacency_from_edges(9, &edges, false);
+        let parent = super::centroid_decomposition(&adj);
+        assert_eq!(parent[3], usize::MAX);
+        assert_eq!(parent[0], 3);
+        assert_eq!(parent[4], 3);
+        assert!(
+            parent[7] == usize::MAX && parent[8] == 7 || parent[8] == usize::MAX && parent[7] == 8
+        );
+    }
+
+    #[test]
+    fn is_tree() {
+        use super::{adjacency_from_edges, is_tree};
+
+        let tree = adjacency_from_edges(6, &[(0, 1), (0, 2), (2, 3), (2, 4), (4, 5)], false);
+        assert!(is_tree(&tree));
+        assert!(is_tree(&[vec![]]));
+        assert!(!is_tree(&[]));
+
+        // A forest with the right number of edges in total would need a cycle
+        let forest = adjacency_from_edges(5, &[(0, 1), (2, 3), (3, 4)], false);
+        assert!(!is_tree(&forest));
+        let forest = adjacency_from_edges(5, &[(0, 1), (2, 3), (3, 4), (4, 2)], false);
+        assert!(!is_tree(&forest));
+
+        let cycle = adjacency_from_edges(4, &[(0, 1), (1, 2), (2, 3), (3, 0)], false);
+        assert!(!is_tree(&cycle));
+        let self_loop = adjacency_from_edges(3, &[(0, 1), (1, 1)], false);
+        assert!(!is_tree(&self_loop));
+    }
+
+    #[test]
+    fn reroot_distance_sums() {
+        //     0
+        //    / \
+        //   1   2
+        //  /|   |
+        // 3 4   5
+        //       |
+        //       6
+        let edges = [(0, 1), (0, 2), (1, 3), (1, 4), (2, 5), (5, 6)];
+        let adj = super::adjacency_from_edges(7, &edges, false);
+
+        // Each aggregate is the number of vertices and the sum of their
+        // distances to the root of the subtree
+        let result = super::reroot(
+            &adj,
+            (0, 0),
+            |x: &(usize, usize), y: &(usize, usize)| (x.0 + y.0, x.1 + y.1),
+            |x, _, _| (x.0, x.1 + x.0),
+            |x, _| (x.0 + 1, x.1),
+        );
+
+        for (root, &(count, sum)) in result.iter().enumerate() {
+            let mut dist = [usize::MAX; 7];
+            dist[root] = 0;
+            let mut queue = vec![root];
+            while let Some(v) = queue.pop() {
+                for &u in &adj[v] {
+                    if dist[u] == usize::MAX {
+                        dist[u] = dist[v] + 1;
+                        queue.push(u);
+                    }
+                }
+            }
+            assert_eq!(count, 7);
+            assert_eq!(sum, dist.iter().sum::<usize>());
+        }
+        assert_eq!(result[0].1, 11);
+
+        // Isolated vertices and a forest
+        let adj = super::adjacency_from_edges(4, &[(1, 2)], false);
+        let result = super::reroot(&adj, 0, |x, y| x + y, |x, _, _| x + 1, |x, _| *x);
+        assert_eq!(result, vec![0, 1, 1, 0]);
+    }
+}
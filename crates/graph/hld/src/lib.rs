@@ -8,6 +8,7 @@ pub struct HeavyLightDecomposition {
     heavy: Vec<usize>,
     head: Vec<usize>,
     pos: Vec<usize>,
+    size: Vec<usize>,
     len: usize,
 }
 
@@ -20,6 +21,7 @@ impl HeavyLightDecomposition {
             heavy: vec![usize::MAX; n],
             head: vec![0; n],
             pos: vec![0; n],
+            size: vec![1; n],
             len: 0,
         };
         hld.dfs(adj, 0);
@@ -43,6 +45,11 @@ impl HeavyLightDecomposition {
         self.pos[u]
     }
 
+    /// Returns the number of nodes in the subtree rooted at `u`, including `u`.
+    pub fn subtree_size(&self, u: usize) -> usize {
+        self.size[u]
+    }
+
     /// Finds the heavy path from `u` and returns the size of the subtree rooted
     /// at `u`.
     fn dfs(&mut self, adj: &[Vec<usize>], u: usize) -> usize {
@@ -61,6 +68,7 @@ impl HeavyLightDecomposition {
                 self.heavy[u] = v;
             }
         }
+        self.size[u] = size;
         size
     }
 
@@ -91,10 +99,48 @@ impl<M: Monoid> MonoidTree<M> {
         Self { hld, segtree }
     }
 
+    /// Creates the tree with `values[u]` as the value of each node `u`,
+    /// building the segment tree in O(N) time.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `values` does not have a value for each node.
+    pub fn from_values(adj: &[Vec<usize>], values: &[M::S]) -> Self {
+        assert_eq!(
+            adj.len(),
+            values.len(),
+            "values has to have a value for each node"
+        );
+        let hld = HeavyLightDecomposition::new(adj);
+        let mut ordered = vec![M::identity(); adj.len()];
+        for (u, x) in values.iter().enumerate() {
+            ordered[hld.pos[u]] = x.clone();
+        }
+        let segtree = Segtree::from(ordered);
+        Self { hld, segtree }
+    }
+
     pub fn set(&mut self, u: usize, x: M::S) {
         self.segtree.set(self.hld.pos[u], x);
     }
 
+    /// Sets the value for the edge between `u` and `v`, which is stored in
+    /// whichever of the two is the child of the other.
+    pub fn set_edge(&mut self, u: usize, v: usize, x: M::S) {
+        let child = if self.hld.depth(u) > self.hld.depth(v) {
+            u
+        } else {
+            v
+        };
+        debug_assert!(
+            self.hld.parent(child) == u || self.hld.parent(child) == v,
+            "{} and {} are not adjacent",
+            u,
+            v
+        );
+        self.set(child, x);
+    }
+
     /// Computes the product of the values on the path from `u` to `v`, assuming
     /// each node contains the value for the edge between its parent and itself.
     pub fn edge_prod(&self, u: usize, v: usize) -> M::S {
@@ -108,14 +154,29 @@ impl<M: Monoid> MonoidTree<M> {
     /// Computes the product of the values of the nodes on the path from `u` to
     /// `v`, including `u` and `v`.
     pub fn node_prod(&self, u: usize, v: usize) -> M::S {
+        self.node_prod_with_lca(u, v).0
+    }
+
+    /// Same as `node_prod`, but also returns the lowest common ancestor of `u`
+    /// and `v`.
+    pub fn node_prod_with_lca(&self, u: usize, v: usize) -> (M::S, usize) {
         let (prod, u, v) = self.inner_prod(u, v);
-        M::binary_operation(
+        let prod = M::binary_operation(
             &prod,
             &self.segtree.prod(self.hld.pos(u), self.hld.pos(v) + 1),
-        )
+        );
+        (prod, u)
     }
 
+    /// Combines the values on the path outside the heavy path containing the
+    /// LCA. The values are combined in an order that does not depend on the
+    /// path direction, so the monoid has to be commutative, and swapping `u`
+    /// and `v` gives the identical result even if the operation is not exactly
+    /// associative, as with floating-point numbers.
     fn inner_prod(&self, mut u: usize, mut v: usize) -> (M::S, usize, usize) {
+        if u > v {
+            mem::swap(&mut u, &mut v);
+        }
         let mut prod = M::identity();
         while self.hld.head(u) != self.hld.head(v) {
             if self.hld.depth(self.hld.head(u)) > self.hld.depth(self.hld.head(v)) {
@@ -138,6 +199,206 @@ impl<M: Monoid> MonoidTree<M> {
 
 #[cfg(test)]
 mod tests {
+    use acl_segtree::Monoid;
+
+    struct Sum;
+
+    impl Monoid for Sum {
+        type S = u64;
+
+        fn identity() -> u64 {
+            0
+        }
+
+        fn binary_operation(a: &u64, b: &u64) -> u64 {
+            a + b
+        }
+    }
+
+    struct Max;
+
+    impl Monoid for Max {
+        type S = u64;
+
+        fn identity() -> u64 {
+            0
+        }
+
+        fn binary_operation(a: &u64, b: &u64) -> u64 {
+            *a.max(b)
+        }
+    }
+
+    struct FloatSum;
+
+    impl Monoid for FloatSum {
+        type S = f64;
+
+        fn identity() -> f64 {
+            0.0
+        }
+
+        fn binary_operation(a: &f64, b: &f64) -> f64 {
+            a + b
+        }
+    }
+
+    #[test]
+    fn symmetric_prod() {
+        //       0
+        //      / \
+        //     1   2
+        //    /   / \
+        //   3   4   5
+        //   |       |
+        //   6       7
+        let adj = vec![
+            vec![1, 2],
+            vec![0, 3],
+            vec![0, 4, 5],
+            vec![1, 6],
+            vec![2],
+            vec![2, 7],
+            vec![3],
+            vec![5],
+        ];
+
+        let mut tree = super::MonoidTree::<Max>::new(&adj);
+        for (u, x) in [3, 1, 4, 1, 5, 9, 2, 6].into_iter().enumerate() {
+            tree.set(u, x);
+        }
+        assert_eq!(tree.node_prod(6, 4), 5);
+        assert_eq!(tree.node_prod(6, 7), 9);
+        assert_eq!(tree.edge_prod(6, 0), 2);
+
+        // Floating-point addition depends on the order of the operands, which
+        // could differ between directions when the light paths climbed from
+        // both ends have heads at the same depths
+        //        0
+        //      / | \
+        //     1  5  8
+        //     | / \ | \
+        //     2 6 7 9 10
+        //     |
+        //     3
+        //     |
+        //     4
+        let adj = vec![
+            vec![1, 5, 8],
+            vec![0, 2],
+            vec![1, 3],
+            vec![2, 4],
+            vec![3],
+            vec![0, 6, 7],
+            vec![5],
+            vec![5],
+            vec![0, 9, 10],
+            vec![8],
+            vec![8],
+        ];
+        let mut tree = super::MonoidTree::<FloatSum>::new(&adj);
+        let values = [0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 1e16, -1e16, 0.0, 1.0];
+        for (u, &x) in values.iter().enumerate() {
+            tree.set(u, x);
+        }
+        assert_eq!(tree.node_prod(7, 10), tree.node_prod(10, 7));
+        for u in 0..adj.len() {
+            for v in 0..adj.len() {
+                assert_eq!(
+                    tree.node_prod(u, v).to_bits(),
+                    tree.node_prod(v, u).to_bits()
+                );
+                assert_eq!(
+                    tree.edge_prod(u, v).to_bits(),
+                    tree.edge_prod(v, u).to_bits()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn node_prod_with_lca() {
+        //       0
+        //      / \
+        //     1   2
+        //    / \   \
+        //   3   4   5
+        //  /   / \
+        // 6   7   8
+        let adj = vec![
+            vec![1, 2],
+            vec![0, 3, 4],
+            vec![0, 5],
+            vec![1, 6],
+            vec![1, 7, 8],
+            vec![2],
+            vec![3],
+            vec![4],
+            vec![4],
+        ];
+        let parent = [0, 0, 0, 1, 1, 2, 3, 4, 4];
+        let ancestors = |mut u: usize| {
+            let mut ancestors = vec![u];
+            while u != 0 {
+                u = parent[u];
+                ancestors.push(u);
+            }
+            ancestors
+        };
+
+        let mut tree = super::MonoidTree::<Sum>::new(&adj);
+        for u in 0..9 {
+            tree.set(u, 1 << u);
+        }
+        for u in 0..9 {
+            for v in 0..9 {
+                let (prod, lca) = tree.node_prod_with_lca(u, v);
+                let (path_u, path_v) = (ancestors(u), ancestors(v));
+                let expected = *path_u.iter().find(|w| path_v.contains(w)).unwrap();
+                assert_eq!(lca, expected);
+                let path_prod = path_u
+                    .iter()
+                    .chain(&path_v)
+                    .filter(|w| !path_u.contains(w) || !path_v.contains(w))
+                    .map(|w| 1 << w)
+                    .sum::<u64>();
+                assert_eq!(prod, path_prod + (1 << lca));
+                assert_eq!(prod, tree.node_prod(u, v));
+            }
+        }
+    }
+
+    #[test]
+    fn from_values() {
+        let mut state = 88172645463325252u64;
+        let mut next = |bound: u64| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state % bound
+        };
+        let n = 50;
+        let mut adj = vec![vec![]; n];
+        for v in 1..n {
+            let u = next(v as u64) as usize;
+            adj[u].push(v);
+            adj[v].push(u);
+        }
+        let values = (0..n).map(|_| next(1000)).collect::<Vec<_>>();
+
+        let built = super::MonoidTree::<Sum>::from_values(&adj, &values);
+        let mut set = super::MonoidTree::<Sum>::new(&adj);
+        for (u, &x) in values.iter().enumerate() {
+            set.set(u, x);
+        }
+        for u in 0..n {
+            for v in 0..n {
+                assert_eq!(built.node_prod(u, v), set.node_prod(u, v));
+                assert_eq!(built.edge_prod(u, v), set.edge_prod(u, v));
+            }
+        }
+    }
+
     #[test]
     fn single_path() {
         let adj = vec![vec![1], vec![2], vec![3], vec![4], vec![5], vec![]];
@@ -145,5 +406,49 @@ mod tests {
         assert_eq!(hld.head, vec![0, 0, 0, 0, 0, 0]);
         assert_eq!(hld.pos, vec![0, 1, 2, 3, 4, 5]);
         assert_eq!(hld.heavy, vec![1, 2, 3, 4, 5, usize::MAX]);
+        let sizes = (0..6).map(|u| hld.subtree_size(u)).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![6, 5, 4, 3, 2, 1]);
+    }
+
+    #[test]
+    fn subtree_size() {
+        //     0
+        //    / \
+        //   1   2
+        //      /|\
+        //     3 4 5
+        //         |
+        //         6
+        let adj = vec![
+            vec![1, 2],
+            vec![0],
+            vec![0, 3, 4, 5],
+            vec![2],
+            vec![2],
+            vec![2, 6],
+            vec![5],
+        ];
+        let hld = super::HeavyLightDecomposition::new(&adj);
+        let sizes = (0..7).map(|u| hld.subtree_size(u)).collect::<Vec<_>>();
+        assert_eq!(sizes, vec![7, 1, 5, 1, 1, 2, 1]);
+    }
+
+    #[test]
+    fn set_edge() {
+        //     0
+        //    / \
+        //   1   2
+        //      / \
+        //     3   4
+        let adj = vec![vec![1, 2], vec![0], vec![0, 3, 4], vec![2], vec![2]];
+        let mut tree = super::MonoidTree::<Sum>::new(&adj);
+        tree.set_edge(0, 1, 5);
+        tree.set_edge(2, 0, 3);
+        tree.set_edge(2, 3, 7);
+        tree.set_edge(4, 2, 2);
+        assert_eq!(tree.edge_prod(1, 3), 15);
+        assert_eq!(tree.edge_prod(3, 4), 9);
+        assert_eq!(tree.edge_prod(0, 4), 5);
+        assert_eq!(tree.edge_prod(2, 2), 0);
     }
 }
@@ -6,13 +6,30 @@ pub struct Lca {
 }
 
 impl Lca {
-    pub fn new(tree: &Vec<Vec<usize>>) -> Self {
+    pub fn new(tree: &[Vec<usize>]) -> Self {
         let n = tree.len();
         let log_n = (n as f64).log2().ceil() as usize;
 
-        let mut up = vec![vec![0; log_n]; tree.len()];
+        let mut up = vec![vec![0; log_n.max(1)]; tree.len()];
         let mut depth = vec![0; n];
-        dfs_lca(0, 0, 0, tree, &mut up, &mut depth);
+        let mut order = Vec::with_capacity(n);
+        let mut stack = vec![0];
+        while let Some(v) = stack.pop() {
+            order.push(v);
+            for &child in &tree[v] {
+                if child != up[v][0] {
+                    up[child][0] = v;
+                    depth[child] = depth[v] + 1;
+                    stack.push(child);
+                }
+            }
+        }
+        // Every vertex comes after its parent, whose row is then complete
+        for v in order {
+            for i in 1..up[v].len() {
+                up[v][i] = up[up[v][i - 1]][i - 1];
+            }
+        }
         Self { up, depth }
     }
 
@@ -41,33 +58,137 @@ impl Lca {
 
         self.up[a][0]
     }
+
+    /// Returns the vertices on the path from `a` to `b` in order, both
+    /// inclusive, in O(log N + path length).
+    pub fn path_nodes(&self, mut a: usize, mut b: usize) -> Vec<usize> {
+        let l = self.lca(a, b);
+        let mut path = Vec::with_capacity(self.depth[a] + self.depth[b] + 1 - 2 * self.depth[l]);
+        while a != l {
+            path.push(a);
+            a = self.up[a][0];
+        }
+        path.push(l);
+        let mid = path.len();
+        while b != l {
+            path.push(b);
+            b = self.up[b][0];
+        }
+        path[mid..].reverse();
+        path
+    }
+
+    /// Folds `node_value` over the vertices on the path from `a` to `b` in
+    /// order, both inclusive, in O(log N + path length).
+    ///
+    /// `combine` has to be associative with `identity` as its identity
+    /// element, since the two halves of the path meeting at the LCA are folded
+    /// separately. It does not have to be commutative.
+    pub fn path_fold<T, F>(
+        &self,
+        mut a: usize,
+        mut b: usize,
+        node_value: impl Fn(usize) -> T,
+        combine: F,
+        identity: T,
+    ) -> T
+    where
+        F: Fn(T, T) -> T,
+    {
+        let l = self.lca(a, b);
+        let mut left = identity;
+        while a != l {
+            left = combine(left, node_value(a));
+            a = self.up[a][0];
+        }
+        // The half from `b` is visited backwards, so it is folded from the right
+        let mut right = None;
+        while b != l {
+            right = Some(match right {
+                Some(right) => combine(node_value(b), right),
+                None => node_value(b),
+            });
+            b = self.up[b][0];
+        }
+        let left = combine(left, node_value(l));
+        match right {
+            Some(right) => combine(left, right),
+            None => left,
+        }
+    }
+}
+
+/// Same as `Lca`, but stores the binary lifting table in a single contiguous
+/// vector, with the `2^k`-th ancestor of `v` at `up[v * log_n + k]`, for
+/// better cache locality on large trees. The table is built in BFS order, so
+/// deep trees do not overflow the stack.
+pub struct FlatLca {
+    up: Vec<usize>,
+    depth: Vec<usize>,
+    log_n: usize,
 }
 
-fn dfs_lca(
-    v: usize,
-    p: usize,
-    height: usize,
-    tree: &Vec<Vec<usize>>,
-    up: &mut Vec<Vec<usize>>,
-    depth: &mut Vec<usize>,
-) {
-    up[v][0] = p;
-    depth[v] = height;
-
-    for i in 1..up[v].len() {
-        up[v][i] = up[up[v][i - 1]][i - 1];
+impl FlatLca {
+    pub fn new(tree: &[Vec<usize>]) -> Self {
+        let n = tree.len();
+        let log_n = (n as f64).log2().ceil() as usize;
+
+        let mut up = vec![0; n * log_n];
+        let mut depth = vec![0; n];
+        let mut order = Vec::with_capacity(n);
+        let mut visited = vec![false; n];
+        if n > 0 {
+            order.push(0);
+            visited[0] = true;
+        }
+        let mut i = 0;
+        while let Some(&v) = order.get(i) {
+            i += 1;
+            for k in 1..log_n {
+                up[v * log_n + k] = up[up[v * log_n + k - 1] * log_n + k - 1];
+            }
+            for &child in &tree[v] {
+                if !visited[child] {
+                    visited[child] = true;
+                    depth[child] = depth[v] + 1;
+                    up[child * log_n] = v;
+                    order.push(child);
+                }
+            }
+        }
+        Self { up, depth, log_n }
     }
 
-    for &child in &tree[v] {
-        if child != p {
-            dfs_lca(child, v, height + 1, tree, up, depth);
+    /// Finds the lowest common ancestor of two vertices in O(log N).
+    pub fn lca(&self, mut a: usize, mut b: usize) -> usize {
+        if self.depth[a] < self.depth[b] {
+            std::mem::swap(&mut a, &mut b);
+        }
+
+        for k in (0..self.log_n).rev() {
+            if self.depth[a].saturating_sub(1 << k) >= self.depth[b] {
+                a = self.up[a * self.log_n + k];
+            }
         }
+
+        if a == b {
+            return a;
+        }
+
+        for k in (0..self.log_n).rev() {
+            if self.up[a * self.log_n + k] != self.up[b * self.log_n + k] {
+                a = self.up[a * self.log_n + k];
+                b = self.up[b * self.log_n + k];
+            }
+        }
+
+        self.up[a * self.log_n]
     }
 }
 
 /// Finds the lowest common ancestor of two vertices for each query using
 /// Tarjan's offline algorithm.
-pub fn offline_lca(adj: &Vec<Vec<usize>>, root: usize, queries: &[(usize, usize)]) -> Vec<usize> {
+pub fn offline_lca(adj: &[Vec<usize>], root: usize, queries: &[(usize, usize)]) -> Vec<usize> {
     let n = adj.len();
     let mut dsu = Dsu::new(n);
     let mut ancestors = vec![0; n];
@@ -80,42 +201,157 @@ pub fn offline_lca(adj: &Vec<Vec<usize>>, root: usize, queries: &[(usize, usize)
         qs[v].push((u, i));
     }
 
-    dfs_offline_lca(
-        root,
-        adj,
-        &mut dsu,
-        &mut ancestors,
-        &mut visited,
-        &qs,
-        &mut answers,
-    );
+    // Each entry holds a vertex and the index of its next neighbor to visit
+    let mut stack = vec![(root, 0)];
+    ancestors[root] = root;
+    visited[root] = true;
+    while let Some(&mut (v, ref mut i)) = stack.last_mut() {
+        if let Some(&u) = adj[v].get(*i) {
+            *i += 1;
+            if !visited[u] {
+                ancestors[u] = u;
+                visited[u] = true;
+                stack.push((u, 0));
+            }
+            continue;
+        }
+
+        for &(u, i) in &qs[v] {
+            if visited[u] {
+                answers[i] = ancestors[dsu.leader(u)];
+            }
+        }
+        stack.pop();
+        if let Some(&(p, _)) = stack.last() {
+            dsu.merge(p, v);
+            ancestors[dsu.leader(p)] = p;
+        }
+    }
 
     answers
 }
 
-fn dfs_offline_lca(
-    v: usize,
-    adj: &Vec<Vec<usize>>,
-    dsu: &mut Dsu,
-    ancestors: &mut [usize],
-    visited: &mut Vec<bool>,
-    qs: &Vec<Vec<(usize, usize)>>,
-    answers: &mut [usize],
-) {
-    ancestors[v] = v;
-    visited[v] = true;
-
-    for &u in &adj[v] {
-        if !visited[u] {
-            dfs_offline_lca(u, adj, dsu, ancestors, visited, qs, answers);
-            dsu.merge(v, u);
-            ancestors[dsu.leader(v)] = v;
+#[cfg(test)]
+mod tests {
+    use super::Lca;
+
+    #[test]
+    fn path_nodes() {
+        //       0
+        //      / \
+        //     1   2
+        //    / \   \
+        //   3   4   5
+        //   |
+        //   6
+        let tree = vec![
+            vec![1, 2],
+            vec![0, 3, 4],
+            vec![0, 5],
+            vec![1, 6],
+            vec![1],
+            vec![2],
+            vec![3],
+        ];
+        let lca = Lca::new(&tree);
+        assert_eq!(lca.path_nodes(6, 5), vec![6, 3, 1, 0, 2, 5]);
+        assert_eq!(lca.path_nodes(4, 6), vec![4, 1, 3, 6]);
+        assert_eq!(lca.path_nodes(0, 6), vec![0, 1, 3, 6]);
+        assert_eq!(lca.path_nodes(6, 1), vec![6, 3, 1]);
+        assert_eq!(lca.path_nodes(5, 5), vec![5]);
+    }
+
+    #[test]
+    fn path_fold() {
+        let tree = vec![
+            vec![1, 2],
+            vec![0, 3, 4],
+            vec![0, 5],
+            vec![1, 6],
+            vec![1],
+            vec![2],
+            vec![3],
+        ];
+        let lca = Lca::new(&tree);
+        for a in 0..tree.len() {
+            for b in 0..tree.len() {
+                let path = lca.path_nodes(a, b);
+                let depth_sum = lca.path_fold(a, b, |v| lca.depth[v], |x, y| x + y, 0);
+                assert_eq!(depth_sum, path.iter().map(|&v| lca.depth[v]).sum());
+
+                // Concatenation is not commutative, so this checks the order
+                let concat = lca.path_fold(a, b, |v| vec![v], |x, y| [x, y].concat(), vec![]);
+                assert_eq!(concat, path);
+            }
         }
     }
 
-    for &(u, i) in &qs[v] {
-        if visited[u] {
-            answers[i] = ancestors[dsu.leader(u)];
+    #[test]
+    fn lca_deep() {
+        let n = 200_000;
+        let mut tree = vec![vec![]; n];
+        for v in 1..n {
+            tree[v - 1].push(v);
+            tree[v].push(v - 1);
         }
+        let lca = Lca::new(&tree);
+        assert_eq!(lca.lca(n - 1, n / 2), n / 2);
+        assert_eq!(lca.lca(n / 3, n - 1), n / 3);
+        assert_eq!(lca.path_nodes(n - 3, n - 1), vec![n - 3, n - 2, n - 1]);
+        assert_eq!(Lca::new(&[vec![]]).lca(0, 0), 0);
+    }
+
+    #[test]
+    fn flat_lca() {
+        let mut state = 88172645463325252u64;
+        let mut next = |bound: usize| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % bound as u64) as usize
+        };
+        assert_eq!(super::FlatLca::new(&[vec![]]).lca(0, 0), 0);
+        for n in [2, 3, 5, 8, 100, 1000] {
+            let mut tree = vec![vec![]; n];
+            for v in 1..n {
+                let p = next(v);
+                tree[p].push(v);
+                tree[v].push(p);
+            }
+            let lca = Lca::new(&tree);
+            let flat = super::FlatLca::new(&tree);
+            for _ in 0..1000 {
+                let (a, b) = (next(n), next(n));
+                assert_eq!(flat.lca(a, b), lca.lca(a, b));
+            }
+        }
+
+        // A path deep enough to overflow the stack with a recursive DFS
+        let n = 200_000;
+        let mut tree = vec![vec![]; n];
+        for v in 1..n {
+            tree[v - 1].push(v);
+            tree[v].push(v - 1);
+        }
+        let flat = super::FlatLca::new(&tree);
+        assert_eq!(flat.lca(n - 1, n / 2), n / 2);
+        assert_eq!(flat.lca(0, n - 1), 0);
+    }
+
+    #[test]
+    fn offline_lca_deep() {
+        let n = 100_000;
+        let mut adj = vec![vec![]; n];
+        for v in 1..n {
+            adj[v - 1].push(v);
+            adj[v].push(v - 1);
+        }
+        let queries = [(0, n - 1), (n - 1, n / 2), (n / 3, n / 2), (7, 7)];
+        let answers = super::offline_lca(&adj, 0, &queries);
+        assert_eq!(answers, vec![0, n / 2, n / 3, 7]);
+
+        // The same path rooted in the middle
+        let answers = super::offline_lca(&adj, n / 2, &[(0, n - 1), (0, 1), (n - 1, n - 2)]);
+        assert_eq!(answers, vec![n / 2, 1, n - 2]);
     }
 }
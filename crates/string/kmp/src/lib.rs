@@ -14,16 +14,125 @@
 /// println!("{:?}", pi);  // Output will be [0, 0, 0, 0, 1, 2, 3]
 /// ```
 pub fn kmp_prefix(s: &str) -> Vec<usize> {
+    prefix_function(s.as_bytes())
+}
+
+/// Finds the starting byte offsets of all occurrences of `pattern` in `text`,
+/// including overlapping ones, from the prefix function of `pattern`, a
+/// separator, and `text`. An empty pattern occurs at every offset from 0 to
+/// `text.len()`.
+///
+/// # Example
+///
+/// ```
+/// # use kmp::prefix_occurrences;
+/// assert_eq!(prefix_occurrences("aba", "ababa"), vec![0, 2]);
+/// ```
+pub fn prefix_occurrences(pattern: &str, text: &str) -> Vec<usize> {
+    let m = pattern.len();
+    if m == 0 {
+        return (0..=text.len()).collect();
+    }
+
+    // `None` acts as a separator that matches no byte of either string.
+    let s = pattern
+        .bytes()
+        .map(Some)
+        .chain(Some(None))
+        .chain(text.bytes().map(Some))
+        .collect::<Vec<_>>();
+    prefix_function(&s)
+        .into_iter()
+        .enumerate()
+        .skip(m + 1)
+        .filter(|&(_, length)| length == m)
+        .map(|(i, _)| i - 2 * m)
+        .collect()
+}
+
+/// Runs the KMP matching automaton of `pattern` over `text` and returns the
+/// state after each byte of `text`, that is, the length of the longest prefix
+/// of `pattern` that ends there. A state equal to `pattern.len()` marks a full
+/// match.
+///
+/// # Example
+///
+/// ```
+/// # use kmp::prefix_states;
+/// assert_eq!(prefix_states("abaabab", "abab"), vec![1, 2, 3, 1, 2, 3, 4]);
+/// ```
+pub fn prefix_states(text: &str, pattern: &str) -> Vec<usize> {
+    let (text, pattern) = (text.as_bytes(), pattern.as_bytes());
+    let m = pattern.len();
+    if m == 0 {
+        return vec![0; text.len()];
+    }
+
+    let pi = prefix_function(pattern);
+    let mut j = 0;
+    text.iter()
+        .map(|&c| {
+            if j == m {
+                j = pi[j - 1];
+            }
+            while j > 0 && c != pattern[j] {
+                j = pi[j - 1];
+            }
+            if c == pattern[j] {
+                j += 1;
+            }
+            j
+        })
+        .collect()
+}
+
+/// Checks if `s` is a cyclic rotation of `t` by searching for `s` in `t`
+/// repeated twice with the KMP matching automaton. Strings of different
+/// lengths are never rotations of each other, and the empty string is a
+/// rotation of itself.
+///
+/// # Example
+///
+/// ```
+/// # use kmp::is_rotation;
+/// assert!(is_rotation("cdeab", "abcde"));
+/// assert!(!is_rotation("acbde", "abcde"));
+/// ```
+pub fn is_rotation(s: &str, t: &str) -> bool {
+    if s.len() != t.len() {
+        return false;
+    }
+    s.is_empty() || prefix_states(&t.repeat(2), s).contains(&s.len())
+}
+
+/// Computes the length of the longest common prefix of the suffixes of `s`
+/// starting at `i` and `j` by comparing them byte by byte, which takes time
+/// proportional to the answer and no extra memory.
+///
+/// # Example
+///
+/// ```
+/// # use kmp::lcp;
+/// assert_eq!(lcp(b"abcabcabd", 0, 3), 5);
+/// ```
+pub fn lcp(s: &[u8], i: usize, j: usize) -> usize {
+    s[i..]
+        .iter()
+        .zip(&s[j..])
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+fn prefix_function<T: PartialEq>(s: &[T]) -> Vec<usize> {
     let n = s.len();
     let mut pi = vec![0; n];
-    let s_bytes = s.as_bytes();
 
     for i in 1..n {
         let mut j = pi[i - 1];
-        while j > 0 && s_bytes[i] != s_bytes[j] {
+        while j > 0 && s[i] != s[j] {
             j = pi[j - 1];
         }
-        if s_bytes[i] == s_bytes[j] {
+        if s[i] == s[j] {
             j += 1;
         }
         pi[i] = j;
@@ -31,3 +140,96 @@ pub fn kmp_prefix(s: &str) -> Vec<usize> {
 
     pi
 }
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn prefix_occurrences() {
+        let naive = |pattern: &str, text: &str| {
+            (0..=text.len().saturating_sub(pattern.len()))
+                .filter(|&i| text.as_bytes()[i..].starts_with(pattern.as_bytes()))
+                .collect::<Vec<_>>()
+        };
+
+        let text = "abaababaabaababaababa";
+        for pattern in [
+            "a",
+            "aba",
+            "abaab",
+            "ababaab",
+            "bb",
+            "abaababaabaababaababa",
+        ] {
+            assert_eq!(
+                super::prefix_occurrences(pattern, text),
+                naive(pattern, text)
+            );
+        }
+        assert_eq!(super::prefix_occurrences("aaa", "aaaaa"), vec![0, 1, 2]);
+        assert_eq!(super::prefix_occurrences("abc", "ab"), vec![]);
+        assert_eq!(super::prefix_occurrences("", "ab"), vec![0, 1, 2]);
+        assert_eq!(super::prefix_occurrences("", ""), vec![0]);
+        assert_eq!(super::prefix_occurrences("a", ""), vec![]);
+        assert_eq!(super::prefix_occurrences("\0", "a\0\0"), vec![1, 2]);
+    }
+
+    #[test]
+    fn prefix_states() {
+        let text = "aabaabaaabaab";
+        let states = super::prefix_states(text, "aabaab");
+        assert_eq!(states, vec![1, 2, 3, 4, 5, 6, 4, 5, 2, 3, 4, 5, 6]);
+        let ends = states
+            .iter()
+            .enumerate()
+            .filter(|&(_, &state)| state == 6)
+            .map(|(i, _)| i + 1 - 6)
+            .collect::<Vec<_>>();
+        assert_eq!(ends, super::prefix_occurrences("aabaab", text));
+
+        assert_eq!(super::prefix_states("abc", ""), vec![0, 0, 0]);
+        assert_eq!(super::prefix_states("", "abc"), vec![]);
+    }
+
+    #[test]
+    fn is_rotation() {
+        let t = "abaabab";
+        for i in 0..t.len() {
+            let rotated = format!("{}{}", &t[i..], &t[..i]);
+            assert!(super::is_rotation(&rotated, t));
+        }
+        // Same letters and length, but in no rotated order
+        assert!(!super::is_rotation("aabbaab", t));
+        assert!(!super::is_rotation("abab", t));
+        assert!(!super::is_rotation("", "a"));
+        assert!(super::is_rotation("", ""));
+        assert!(super::is_rotation("éa", "aé"));
+    }
+
+    #[test]
+    fn lcp() {
+        // The longest length at which both suffixes have the same prefix
+        let naive = |s: &[u8], i: usize, j: usize| {
+            (0..=s.len() - i.max(j))
+                .rev()
+                .find(|&k| s[i..i + k] == s[j..j + k])
+                .unwrap()
+        };
+
+        // Overlapping repeats, where the common prefix runs past `j`
+        let s = b"abaababaabaababaababa";
+        for i in 0..=s.len() {
+            for j in 0..=s.len() {
+                assert_eq!(super::lcp(s, i, j), naive(s, i, j), "{} {}", i, j);
+            }
+        }
+        assert_eq!(super::lcp(b"aaaaaa", 0, 1), 5);
+        assert_eq!(super::lcp(b"abab", 0, 2), 2);
+        assert_eq!(super::lcp(b"ab", 0, 1), 0);
+        assert_eq!(super::lcp(b"", 0, 0), 0);
+
+        // A long run of equal bytes
+        let s = vec![b'a'; 1_000_000];
+        assert_eq!(super::lcp(&s, 0, 1), 999_999);
+        assert_eq!(super::lcp(&s, 500_000, 0), 500_000);
+    }
+}